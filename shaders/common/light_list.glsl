@@ -0,0 +1,43 @@
+// Point lights and spotlights of a `LightList`, read from shader storage buffers when
+// LIGHT_LIST_SSBO is defined and from buffer textures otherwise. Expects the structs of
+// light_types.glsl to be declared by the including shader.
+
+uniform int pointLightCount;
+uniform int spotlightCount;
+
+#ifdef LIGHT_LIST_SSBO
+layout(std430) readonly buffer PointLightBuffer {
+    vec4 pointLightData[];
+};
+layout(std430) readonly buffer SpotlightBuffer {
+    vec4 spotlightData[];
+};
+
+#define POINT_LIGHT_TEXEL(i) pointLightData[i]
+#define SPOTLIGHT_TEXEL(i) spotlightData[i]
+#else
+uniform samplerBuffer pointLightData;
+uniform samplerBuffer spotlightData;
+
+#define POINT_LIGHT_TEXEL(i) texelFetch(pointLightData, i)
+#define SPOTLIGHT_TEXEL(i) texelFetch(spotlightData, i)
+#endif
+
+PointLight getPointLight(int i) {
+    vec4 a = POINT_LIGHT_TEXEL(i * 4);
+    vec4 b = POINT_LIGHT_TEXEL(i * 4 + 1);
+    vec4 c = POINT_LIGHT_TEXEL(i * 4 + 2);
+    vec4 d = POINT_LIGHT_TEXEL(i * 4 + 3);
+
+    return PointLight(a.xyz, b.xyz, c.xyz, d.xyz, a.w, b.w, c.w);
+}
+
+Spotlight getSpotlight(int i) {
+    vec4 a = SPOTLIGHT_TEXEL(i * 5);
+    vec4 b = SPOTLIGHT_TEXEL(i * 5 + 1);
+    vec4 c = SPOTLIGHT_TEXEL(i * 5 + 2);
+    vec4 d = SPOTLIGHT_TEXEL(i * 5 + 3);
+    vec4 e = SPOTLIGHT_TEXEL(i * 5 + 4);
+
+    return Spotlight(a.xyz, b.xyz, d.w, e.w, c.xyz, d.xyz, e.xyz, a.w, b.w, c.w);
+}
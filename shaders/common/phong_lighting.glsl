@@ -0,0 +1,83 @@
+// Expects `material` and `TexCoord` to be declared by the including shader
+
+vec3 calculateDirectionLighting(DirLight dirLight, vec3 fragPos, vec3 camPos, vec3 norm) {
+    vec3 lightDir = normalize(-dirLight.direction);
+    vec3 cameraDir = normalize(camPos - fragPos);
+
+    vec3 diffuseTexel = vec3(texture(material.diffuse, TexCoord));
+    vec3 specularTexel = vec3(texture(material.specular, TexCoord));
+
+    // Ambient lighting
+    vec3 ambient = dirLight.ambient * diffuseTexel;
+
+    // Diffuse lighting
+    float diff = max(dot(norm, lightDir), 0.0);
+    vec3 diffuse = dirLight.diffuse * diff * diffuseTexel;
+
+    // Specular lighting
+    vec3 reflectDir = reflect(-lightDir, norm);
+    float spec = pow(max(dot(reflectDir, cameraDir), 0.0), material.shininess);
+    vec3 specular = dirLight.specular * spec * specularTexel;
+
+    return ambient + diffuse + specular;
+}
+
+vec3 calculatePointLighting(PointLight pointLight, vec3 fragPos, vec3 camPos, vec3 norm) {
+    vec3 lightDir = normalize(pointLight.position - fragPos);
+    vec3 cameraDir = normalize(camPos - fragPos);
+
+    vec3 diffuseTexel = vec3(texture(material.diffuse, TexCoord));
+    vec3 specularTexel = vec3(texture(material.specular, TexCoord));
+
+    // Light strength attenuation
+    float lightDist = distance(pointLight.position, fragPos);
+    float attenuation = 1.0 / (pointLight.constant +
+                pointLight.linear * lightDist +
+                pointLight.quadratic * pow(lightDist, 2.0));
+
+    // Ambient lighting
+    vec3 ambient = pointLight.ambient * diffuseTexel;
+
+    // Diffuse lighting
+    float diff = max(dot(norm, lightDir), 0.0);
+    vec3 diffuse = pointLight.diffuse * diff * diffuseTexel;
+
+    // Specular lighting
+    vec3 reflectDir = reflect(-lightDir, norm);
+    float spec = pow(max(dot(reflectDir, cameraDir), 0.0), material.shininess);
+    vec3 specular = pointLight.specular * spec * specularTexel;
+
+    return (ambient + diffuse + specular) * attenuation;
+}
+
+vec3 calculateSpotlightLighting(Spotlight spotlight, vec3 fragPos, vec3 camPos, vec3 norm) {
+    vec3 lightDir = normalize(spotlight.position - fragPos);
+    vec3 cameraDir = normalize(camPos - fragPos);
+
+    vec3 diffuseTexel = vec3(texture(material.diffuse, TexCoord));
+    vec3 specularTexel = vec3(texture(material.specular, TexCoord));
+
+    // Light strength attenuation
+    float lightDist = distance(spotlight.position, fragPos);
+    float attenuation = 1.0 / (spotlight.constant +
+                spotlight.linear * lightDist +
+                spotlight.quadratic * pow(lightDist, 2.0));
+
+    // Spotlight effect
+    float theta = dot(lightDir, normalize(-spotlight.direction));
+    float intensity = clamp((theta - spotlight.outerCutoff) / (spotlight.innerCutoff - spotlight.outerCutoff), 0.0, 1.0);
+
+    // Ambient lighting
+    vec3 ambient = spotlight.ambient * diffuseTexel;
+
+    // Diffuse lighting
+    float diff = max(dot(norm, lightDir), 0.0);
+    vec3 diffuse = spotlight.diffuse * diff * diffuseTexel;
+
+    // Specular lighting
+    vec3 reflectDir = reflect(-lightDir, norm);
+    float spec = pow(max(dot(reflectDir, cameraDir), 0.0), material.shininess);
+    vec3 specular = spotlight.specular * spec * specularTexel;
+
+    return (ambient + diffuse + specular) * attenuation * intensity;
+}
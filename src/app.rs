@@ -0,0 +1,365 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use glfw::{
+    Action, Context, ContextCreationApi, CursorMode, Glfw, GlfwReceiver, Key, OpenGlProfileHint,
+    PWindow, WindowEvent, WindowHint, WindowMode,
+};
+use image::RgbaImage;
+
+use crate::{FrameRecorder, GlContext, MovementInput, OffscreenTarget};
+
+type WindowEvents = GlfwReceiver<(f64, WindowEvent)>;
+
+// Environment variables that make `Runner::run` render headlessly into a PNG, which is how
+// batch jobs and the golden image tests run the section binaries
+const HEADLESS_OUTPUT_VAR: &str = "LEARN_OPENGL_HEADLESS_OUTPUT";
+const HEADLESS_FRAMES_VAR: &str = "LEARN_OPENGL_HEADLESS_FRAMES";
+const HEADLESS_SIZE_VAR: &str = "LEARN_OPENGL_HEADLESS_SIZE";
+const HEADLESS_BACKEND_VAR: &str = "LEARN_OPENGL_HEADLESS_BACKEND";
+
+// Environment variables that make `Runner::run` record every frame, see `Runner::record`
+const RECORD_VAR: &str = "LEARN_OPENGL_RECORD";
+const RECORD_FPS_VAR: &str = "LEARN_OPENGL_RECORD_FPS";
+
+/// Timestep of frames rendered through the environment variables, so animated scenes end up
+/// at the same time on every run.
+const HEADLESS_DT: f32 = 1.0 / 60.0;
+
+/// A demo driven by a `Runner`, which owns the window and event loop and calls into the app
+/// every frame.
+pub trait App: Sized {
+    /// Creates the app once the window's context is current, e.g. to load shaders and
+    /// textures.
+    fn init(context: &GlContext, window: &mut PWindow) -> Self;
+
+    /// Advances the scene by `dt` seconds. Held keys can be polled from `window`.
+    fn update(&mut self, _window: &PWindow, _dt: f32) {}
+
+    /// Draws a frame, which is presented once this returns.
+    fn render(&mut self, context: &GlContext, window: &PWindow);
+
+    /// Handles a window event. By the time this is called, resizes have already updated the
+    /// viewport and escape has already closed the window, unless the runner was told not to.
+    fn on_event(&mut self, _window: &mut PWindow, _event: &WindowEvent) {}
+}
+
+/// How `Runner::run_headless` creates its context. Every backend still goes through a hidden
+/// GLFW window, so machines without a display need `xvfb-run` or a GLFW built for OSMesa.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeadlessBackend {
+    /// The platform's usual context API, e.g. GLX. Set `LIBGL_ALWAYS_SOFTWARE=1` to render
+    /// with llvmpipe on machines without a GPU.
+    #[default]
+    Native,
+    Egl,
+    /// Mesa's software rasterizer, which needs no GPU at all.
+    OsMesa,
+}
+
+impl HeadlessBackend {
+    /// Parses `native`, `egl` or `osmesa`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "native" => Some(HeadlessBackend::Native),
+            "egl" => Some(HeadlessBackend::Egl),
+            "osmesa" => Some(HeadlessBackend::OsMesa),
+            _ => None,
+        }
+    }
+
+    fn to_glfw(self) -> ContextCreationApi {
+        match self {
+            HeadlessBackend::Native => ContextCreationApi::Native,
+            HeadlessBackend::Egl => ContextCreationApi::Egl,
+            HeadlessBackend::OsMesa => ContextCreationApi::OsMesa,
+        }
+    }
+}
+
+/// Creates a window with a core profile context and runs an `App` in it until it's closed, or
+/// renders a fixed number of frames without showing anything.
+#[derive(Clone, Debug)]
+pub struct Runner {
+    title: String,
+    width: u32,
+    height: u32,
+    gl_version: (u32, u32),
+    capture_cursor: bool,
+    close_on_escape: bool,
+    headless_backend: HeadlessBackend,
+    screenshot_key: Option<Key>,
+    recording: Option<(PathBuf, u32)>,
+}
+
+impl Runner {
+    /// An 800x600 window with an OpenGL 3.3 context that closes on escape and saves a screenshot
+    /// on F12.
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            width: 800,
+            height: 600,
+            gl_version: (3, 3),
+            capture_cursor: false,
+            close_on_escape: true,
+            headless_backend: HeadlessBackend::default(),
+            screenshot_key: Some(Key::F12),
+            recording: None,
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn gl_version(mut self, major: u32, minor: u32) -> Self {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    /// Hides the cursor and keeps it in the window, for mouse look.
+    pub fn capture_cursor(mut self, capture_cursor: bool) -> Self {
+        self.capture_cursor = capture_cursor;
+        self
+    }
+
+    pub fn close_on_escape(mut self, close_on_escape: bool) -> Self {
+        self.close_on_escape = close_on_escape;
+        self
+    }
+
+    pub fn headless_backend(mut self, backend: HeadlessBackend) -> Self {
+        self.headless_backend = backend;
+        self
+    }
+
+    /// The key that saves the current frame to `screenshot_<unix time in ms>.png` in the
+    /// working directory, or `None` to leave every key to the app.
+    pub fn screenshot_key(mut self, key: Option<Key>) -> Self {
+        self.screenshot_key = key;
+        self
+    }
+
+    /// Saves every frame into `dir` with a `FrameRecorder`. The app is updated with a fixed
+    /// timestep of `1 / fps` seconds instead of the time that actually passed, so the frames
+    /// play back at the right speed however long they took to render and save.
+    pub fn record(mut self, dir: impl Into<PathBuf>, fps: u32) -> Self {
+        self.recording = Some((dir.into(), fps));
+        self
+    }
+
+    /// Opens the window and runs `A` until the window is closed.
+    ///
+    /// With `LEARN_OPENGL_HEADLESS_OUTPUT` set to a PNG path, no window is shown. Instead
+    /// `LEARN_OPENGL_HEADLESS_FRAMES` frames (1 by default) of 1/60 s are rendered with
+    /// `run_headless` and the last one is saved to that path. `LEARN_OPENGL_HEADLESS_SIZE`
+    /// (e.g. `400x300`) and `LEARN_OPENGL_HEADLESS_BACKEND` (`native`, `egl` or `osmesa`)
+    /// override the size and backend.
+    ///
+    /// Setting `LEARN_OPENGL_RECORD` to a directory records into it like `record`, at
+    /// `LEARN_OPENGL_RECORD_FPS` frames per second (60 by default).
+    pub fn run<A: App>(&self) -> Result<(), String> {
+        if let Some(output) = std::env::var_os(HEADLESS_OUTPUT_VAR) {
+            return self.run_headless_from_env::<A>(Path::new(&output));
+        }
+
+        let mut recording = self.recording.clone();
+        if let Some(dir) = env_var(RECORD_VAR) {
+            let fps = match env_var(RECORD_FPS_VAR) {
+                Some(fps) => fps
+                    .parse()
+                    .map_err(|_| format!("{RECORD_FPS_VAR} should be a number of frames"))?,
+                None => 60,
+            };
+            recording = Some((PathBuf::from(dir), fps));
+        }
+        let mut recorder = match recording {
+            Some((dir, fps)) => Some(FrameRecorder::new(dir, fps)?),
+            None => None,
+        };
+
+        let (mut glfw, mut window, events) = self.create_window(None)?;
+        window.set_all_polling(true);
+        if self.capture_cursor {
+            window.set_cursor_mode(CursorMode::Disabled);
+        }
+
+        // SAFETY: the context was just made current and lives in `window`, which outlives both
+        // the token and the app
+        let context = unsafe { GlContext::load_with(|s| window.get_proc_address(s)) };
+        let mut app = A::init(&context, &mut window);
+
+        let mut last_frame = glfw.get_time();
+
+        while !window.should_close() {
+            // Process window events
+            let mut take_screenshot = false;
+            glfw.poll_events();
+            for (_, event) in glfw::flush_messages(&events) {
+                match event {
+                    WindowEvent::FramebufferSize(w, h) => unsafe { gl::Viewport(0, 0, w, h) },
+                    WindowEvent::Key(Key::Escape, _, Action::Press, _) if self.close_on_escape => {
+                        window.set_should_close(true)
+                    }
+                    WindowEvent::Key(key, _, Action::Press, _)
+                        if Some(key) == self.screenshot_key =>
+                    {
+                        take_screenshot = true
+                    }
+                    _ => {}
+                }
+                app.on_event(&mut window, &event);
+            }
+
+            let now = glfw.get_time();
+            let delta_time = match &recorder {
+                Some(recorder) => recorder.dt(),
+                None => (now - last_frame) as f32,
+            };
+            last_frame = now;
+
+            app.update(&window, delta_time);
+            app.render(&context, &window);
+
+            // Read the frame back before it's presented
+            if take_screenshot || recorder.is_some() {
+                let (width, height) = window.get_framebuffer_size();
+                let frame = crate::read_default_framebuffer(&context, width as u32, height as u32);
+
+                if take_screenshot {
+                    let millis = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |time| time.as_millis());
+                    let path = format!("screenshot_{millis}.png");
+                    match frame.save(&path) {
+                        Ok(()) => println!("Saved screenshot to {path}"),
+                        Err(e) => eprintln!("Failed to save {path}: {e}"),
+                    }
+                }
+                if let Some(recorder) = &mut recorder {
+                    recorder.save_frame(&frame)?;
+                }
+            }
+
+            window.swap_buffers();
+        }
+
+        if let Some(recorder) = &recorder {
+            println!(
+                "Recorded {} frames to {}",
+                recorder.frames(),
+                recorder.dir().display()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Runs `A` for `frames` frames of `dt` seconds each in a hidden window and returns the
+    /// last one. Frames are rendered into an `OffscreenTarget` the size of the window, which is
+    /// bound before every update, so apps that switch framebuffers have to switch back to it
+    /// instead of to the default one. No events are delivered.
+    pub fn run_headless<A: App>(&self, frames: u32, dt: f32) -> Result<RgbaImage, String> {
+        let (_glfw, mut window, _events) = self.create_window(Some(self.headless_backend))?;
+
+        // SAFETY: same as in `run`
+        let context = unsafe { GlContext::load_with(|s| window.get_proc_address(s)) };
+        let target = OffscreenTarget::new(&context, self.width, self.height)?;
+        target.bind();
+        let mut app = A::init(&context, &mut window);
+
+        for _ in 0..frames {
+            target.bind();
+            app.update(&window, dt);
+            app.render(&context, &window);
+        }
+
+        Ok(target.read_pixels())
+    }
+
+    fn run_headless_from_env<A: App>(&self, output: &Path) -> Result<(), String> {
+        let mut runner = self.clone();
+        if let Some(backend) = env_var(HEADLESS_BACKEND_VAR) {
+            runner.headless_backend = HeadlessBackend::from_name(&backend)
+                .ok_or_else(|| format!("Unknown {HEADLESS_BACKEND_VAR} `{backend}`"))?;
+        }
+        if let Some(size) = env_var(HEADLESS_SIZE_VAR) {
+            let (width, height) = size
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .ok_or_else(|| format!("{HEADLESS_SIZE_VAR} should look like 400x300"))?;
+            runner = runner.size(width, height);
+        }
+        let frames = match env_var(HEADLESS_FRAMES_VAR) {
+            Some(frames) => frames
+                .parse()
+                .map_err(|_| format!("{HEADLESS_FRAMES_VAR} should be a number of frames"))?,
+            None => 1,
+        };
+
+        let image = runner.run_headless::<A>(frames, HEADLESS_DT)?;
+        image
+            .save(output)
+            .map_err(|e| format!("Failed to save {}: {e}", output.display()))
+    }
+
+    /// Creates the window and makes its context current. Headless windows are hidden, and
+    /// report GLFW errors through the result instead of panicking.
+    fn create_window(
+        &self,
+        headless: Option<HeadlessBackend>,
+    ) -> Result<(Glfw, PWindow, WindowEvents), String> {
+        // Initialize GLFW
+        let mut glfw = match headless {
+            Some(_) => glfw::init(glfw::log_errors),
+            None => glfw::init(glfw::fail_on_errors),
+        }
+        .map_err(|e| format!("Failed to initialize GLFW: {e}"))?;
+        glfw.window_hint(WindowHint::ContextVersion(
+            self.gl_version.0,
+            self.gl_version.1,
+        ));
+        glfw.window_hint(WindowHint::OpenGlProfile(OpenGlProfileHint::Core));
+        #[cfg(target_os = "macos")]
+        glfw.window_hint(WindowHint::OpenGlForwardCompat(true));
+        if let Some(backend) = headless {
+            glfw.window_hint(WindowHint::Visible(false));
+            glfw.window_hint(WindowHint::ContextCreationApi(backend.to_glfw()));
+        }
+
+        // Create window
+        let (mut window, events) = glfw
+            .create_window(self.width, self.height, &self.title, WindowMode::Windowed)
+            .ok_or_else(|| format!("Failed to create window `{}`", self.title))?;
+        window.make_current();
+
+        Ok((glfw, window, events))
+    }
+}
+
+impl MovementInput {
+    /// Reads the usual keys from `window`: WASD to move, space and left control to go up and
+    /// down, left shift to sprint and left alt to crawl.
+    pub fn from_window(window: &PWindow) -> Self {
+        let held = |key| window.get_key(key) == Action::Press;
+        Self {
+            forward: held(Key::W),
+            backward: held(Key::S),
+            left: held(Key::A),
+            right: held(Key::D),
+            up: held(Key::Space),
+            down: held(Key::LeftControl),
+            sprint: held(Key::LeftShift),
+            crawl: held(Key::LeftAlt),
+        }
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
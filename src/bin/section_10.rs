@@ -5,10 +5,13 @@ extern crate glfw;
 
 use std::os::raw::c_void;
 
-use gl::types::GLfloat;
-use glfw::Context;
-use image::io::Reader as ImageReader;
-use learn_opengl::{Camera, Shader};
+use gl::types::{GLfloat, GLint};
+use glfw::{PWindow, WindowEvent};
+use learn_opengl::{
+    objects::{Buffer, VertexArray},
+    App, Camera, CameraController, GlContext, MovementInput, Runner, Shader, Texture2D,
+    TextureFilter,
+};
 
 #[rustfmt::skip]
 const TRIANGLE_VERTICES: [f32; 40] = [
@@ -54,269 +57,156 @@ const CUBE_POSITIONS: [nalgebra_glm::Vec3; 10] = [
 const CAMERA_SPEED: f32 = 2.5;
 const MOUSE_SENSITIVITY: f32 = 0.2;
 
-fn process_events(
-    window: &mut glfw::Window,
-    events: &glfw::GlfwReceiver<(f64, glfw::WindowEvent)>,
-    delta_time: f32,
-    last_mouse_x: &mut f32,
-    last_mouse_y: &mut f32,
-    camera: &mut Camera,
-) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(w, h) => unsafe {
-                gl::Viewport(0, 0, w, h);
-            },
-            glfw::WindowEvent::Key(glfw::Key::Escape, _, glfw::Action::Press, _) => {
-                window.set_should_close(true)
-            }
-            glfw::WindowEvent::CursorPos(x_pos, y_pos) => {
-                let dx = x_pos as f32 - *last_mouse_x;
-                let dy = *last_mouse_y - y_pos as f32;
-                *last_mouse_x = x_pos as f32;
-                *last_mouse_y = y_pos as f32;
-
-                camera.look_around(dx * MOUSE_SENSITIVITY, dy * MOUSE_SENSITIVITY);
-            }
-            glfw::WindowEvent::Scroll(_, y_offset) => camera.zoom(y_offset as f32),
-            _ => {}
-        }
-    }
-
-    if window.get_key(glfw::Key::W) == glfw::Action::Press {
-        camera.move_front(CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::S) == glfw::Action::Press {
-        camera.move_front(-CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::A) == glfw::Action::Press {
-        camera.move_side(-CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::D) == glfw::Action::Press {
-        camera.move_side(CAMERA_SPEED * delta_time);
-    }
+struct CameraDemo {
+    shader_program: Shader,
+    wood_texture: Texture2D,
+    smile_texture: Texture2D,
+    _vbo: Buffer,
+    _ebo: Buffer,
+    vao: VertexArray,
+    model_location: GLint,
+    view_location: GLint,
+    projection_location: GLint,
+    controller: CameraController,
+    camera: Camera,
 }
 
-fn main() {
-    // Initialize GLFW
-    let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
-
-    // Configure GLFW
-    glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-        glfw::OpenGlProfileHint::Core,
-    ));
-    #[cfg(target_os = "macos")]
-    glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-    // Create a window using GLFW
-    let (mut window, events) = glfw
-        .create_window(800, 600, "Getting Started", glfw::WindowMode::Windowed)
-        .expect("GLFW sh*t itself :(");
-
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_framebuffer_size_polling(true);
-
-    // Capture mouse and set mouse polling
-    window.set_cursor_mode(glfw::CursorMode::Disabled);
-    window.set_cursor_pos_polling(true);
-
-    // Set scroll polling
-    window.set_scroll_polling(true);
-
-    // Load OpenGL function pointers
-    gl::load_with(|sym| window.get_proc_address(sym) as *const _);
-
-    // Enable depth testing
-    unsafe {
-        gl::Enable(gl::DEPTH_TEST);
-    }
+impl App for CameraDemo {
+    fn init(context: &GlContext, window: &mut PWindow) -> Self {
+        // Enable depth testing
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+        }
 
-    let shader_program = unsafe {
-        let shader = Shader::new(
-            "shaders/section_10/vertex.glsl",
-            "shaders/section_10/fragment.glsl",
-        );
+        let shader_program = {
+            let shader = Shader::new(
+                context,
+                "shaders/section_10/vertex.glsl",
+                "shaders/section_10/fragment.glsl",
+            );
 
-        if let Err(e) = shader {
-            panic!("Failed to load shaders:\n{}", e);
-        }
-        shader.unwrap()
-    };
-
-    let wood_texture = unsafe {
-        // Load texture image
-        let img = ImageReader::open("assets/textures/container.jpg")
-            .expect("Unable to open image file")
-            .decode()
-            .unwrap()
-            .flipv();
-        let img_bytes = img.as_bytes();
-
-        // Generate and bind a new texture
-        let mut texture = 0;
-        gl::GenTextures(1, &mut texture);
-        gl::BindTexture(gl::TEXTURE_2D, texture);
-
-        // Configure texture wrapping and filtering options
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-        // Set texture data and generate mipmaps
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGB as i32,
-            img.width() as i32,
-            img.height() as i32,
-            0,
-            gl::RGB,
-            gl::UNSIGNED_BYTE,
-            img_bytes.as_ptr() as *const c_void,
-        );
-        gl::GenerateMipmap(gl::TEXTURE_2D);
-
-        texture
-    };
-
-    let smile_texture = unsafe {
-        // Load texture image
-        let img = ImageReader::open("assets/textures/awesomeface.png")
-            .unwrap()
-            .decode()
-            .unwrap()
-            .flipv();
-        let img_bytes = img.as_bytes();
-
-        // Generate and bind a new texture
-        let mut texture = 0;
-        gl::GenTextures(1, &mut texture);
-        gl::BindTexture(gl::TEXTURE_2D, texture);
-
-        // Configure texture wrapping and filtering options
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-        // Set texture data and generate mipmaps
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGB as i32,
-            img.width() as i32,
-            img.height() as i32,
-            0,
-            gl::RGBA,
-            gl::UNSIGNED_BYTE,
-            img_bytes.as_ptr() as *const c_void,
-        );
-        gl::GenerateMipmap(gl::TEXTURE_2D);
-
-        texture
-    };
-
-    let vao = unsafe {
-        // Create a vertex buffer, element buffer and vertex array object
-        let (mut vbo, mut ebo, mut vao) = (0, 0, 0);
-        gl::GenVertexArrays(1, &mut vao);
-        gl::GenBuffers(1, &mut vbo);
-        gl::GenBuffers(1, &mut ebo);
-
-        // Bind the vertex array first
-        gl::BindVertexArray(vao);
-
-        // Bind a buffer object as a vertex buffer and add data to it
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            std::mem::size_of_val(&TRIANGLE_VERTICES) as isize,
-            &TRIANGLE_VERTICES as *const f32 as *const c_void,
-            gl::STATIC_DRAW,
-        );
+            if let Err(e) = shader {
+                panic!("Failed to load shaders:\n{}", e);
+            }
+            shader.unwrap()
+        };
+
+        let wood_texture = Texture2D::builder()
+            .filter(TextureFilter::Nearest, TextureFilter::Linear)
+            .load(context, "assets/textures/container.jpg")
+            .unwrap();
+
+        let smile_texture = Texture2D::builder()
+            .filter(TextureFilter::Nearest, TextureFilter::Linear)
+            .load(context, "assets/textures/awesomeface.png")
+            .unwrap();
+
+        let (vao, vbo, ebo) = unsafe {
+            // Create a vertex buffer, element buffer and vertex array object
+            let vao = VertexArray::new(context);
+            let vbo = Buffer::new(context);
+            let ebo = Buffer::new(context);
+
+            // Bind the vertex array first
+            vao.bind();
+
+            // Bind a buffer object as a vertex buffer and add data to it
+            vbo.bind(gl::ARRAY_BUFFER);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&TRIANGLE_VERTICES) as isize,
+                &TRIANGLE_VERTICES as *const f32 as *const c_void,
+                gl::STATIC_DRAW,
+            );
 
-        // Bind a buffer object as an element buffer and add data to it
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-        gl::BufferData(
-            gl::ELEMENT_ARRAY_BUFFER,
-            std::mem::size_of_val(&TRIANGLE_ELEMENTS) as isize,
-            &TRIANGLE_ELEMENTS as *const u32 as *const c_void,
-            gl::STATIC_DRAW,
-        );
+            // Bind a buffer object as an element buffer and add data to it
+            ebo.bind(gl::ELEMENT_ARRAY_BUFFER);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                std::mem::size_of_val(&TRIANGLE_ELEMENTS) as isize,
+                &TRIANGLE_ELEMENTS as *const u32 as *const c_void,
+                gl::STATIC_DRAW,
+            );
 
-        let stride = 5 * std::mem::size_of::<f32>() as i32;
+            let stride = 5 * std::mem::size_of::<f32>() as i32;
 
-        // Configure vertex position attributes
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
+            // Configure vertex position attributes
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
 
-        // Configure texture coordinate attributes
-        gl::VertexAttribPointer(
-            1,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            stride,
-            (3 * std::mem::size_of::<f32>()) as *const c_void,
-        );
-        gl::EnableVertexAttribArray(1);
+            // Configure texture coordinate attributes
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * std::mem::size_of::<f32>()) as *const c_void,
+            );
+            gl::EnableVertexAttribArray(1);
 
-        // Unbind vertex array
-        gl::BindVertexArray(0);
+            // Unbind vertex array
+            gl::BindVertexArray(0);
 
-        vao
-    };
+            (vao, vbo, ebo)
+        };
 
-    // Set initial uniform values
-    unsafe {
-        // Configure texture sampler locations
-        shader_program.use_program();
-        gl::Uniform1i(shader_program.get_uniform_location("texture1"), 0);
-        gl::Uniform1i(shader_program.get_uniform_location("texture2"), 1);
-    }
+        // Set initial uniform values
+        unsafe {
+            // Configure texture sampler locations
+            shader_program.use_program();
+            gl::Uniform1i(shader_program.get_uniform_location("texture1"), 0);
+            gl::Uniform1i(shader_program.get_uniform_location("texture2"), 1);
+        }
 
-    // Get uniform locations
-    let (model_location, view_location, projection_location) = unsafe {
-        (
+        // Get uniform locations
+        let (model_location, view_location, projection_location) = (
             shader_program.get_uniform_location("model"),
             shader_program.get_uniform_location("view"),
             shader_program.get_uniform_location("projection"),
-        )
-    };
-
-    let mut last_frame = 0.0;
-
-    let mut last_mouse_x = 0.0;
-    let mut last_mouse_y = 0.0;
+        );
 
-    let mut camera = Camera::new(nalgebra_glm::vec3(0.0, 0.0, 3.0), 60.0, -90.0, 0.0);
+        let mut camera = Camera::new(nalgebra_glm::vec3(0.0, 0.0, 3.0), 60.0, -90.0, 0.0);
+        let (width, height) = window.get_framebuffer_size();
+        camera.resize(width, height);
+
+        Self {
+            shader_program,
+            wood_texture,
+            smile_texture,
+            _vbo: vbo,
+            _ebo: ebo,
+            vao,
+            model_location,
+            view_location,
+            projection_location,
+            controller: CameraController::new()
+                .speed(CAMERA_SPEED)
+                .mouse_sensitivity(MOUSE_SENSITIVITY),
+            camera,
+        }
+    }
 
-    while !window.should_close() {
-        // Calculate delta time
-        let current_frame = glfw.get_time() as f32;
-        let delta_time = current_frame - last_frame;
-        last_frame = current_frame;
+    fn on_event(&mut self, _window: &mut PWindow, event: &WindowEvent) {
+        match *event {
+            WindowEvent::FramebufferSize(width, height) => self.camera.resize(width, height),
+            WindowEvent::CursorPos(x, y) => self.controller.cursor_moved(x, y),
+            WindowEvent::Scroll(_, y_offset) => self.camera.zoom(y_offset as f32),
+            _ => {}
+        }
+    }
 
-        process_events(
-            &mut window,
-            &events,
-            delta_time,
-            &mut last_mouse_x,
-            &mut last_mouse_y,
-            &mut camera,
-        );
+    fn update(&mut self, window: &PWindow, dt: f32) {
+        self.controller
+            .update(&mut self.camera, &MovementInput::from_window(window), dt);
+    }
 
+    fn render(&mut self, _context: &GlContext, _window: &PWindow) {
         // View transform
-        let view = camera.look_at_matrix();
+        let view = self.camera.look_at_matrix();
 
         // Projection transform
-        let window_size = window.get_size();
-        let projection = nalgebra_glm::perspective(
-            window_size.0 as f32 / window_size.1 as f32,
-            camera.fov().to_radians(),
-            0.1,
-            100.0,
-        );
+        let projection = self.camera.projection_matrix();
 
         // Rendering commands
         unsafe {
@@ -324,32 +214,30 @@ fn main() {
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
 
             // Use our shader program
-            shader_program.use_program();
+            self.shader_program.use_program();
 
             // Set transform uniform locations
             gl::UniformMatrix4fv(
-                view_location,
+                self.view_location,
                 1,
                 gl::FALSE,
                 nalgebra_glm::value_ptr(&view).as_ptr() as *const GLfloat,
             );
             gl::UniformMatrix4fv(
-                projection_location,
+                self.projection_location,
                 1,
                 gl::FALSE,
                 nalgebra_glm::value_ptr(&projection).as_ptr() as *const GLfloat,
             );
 
             // Bind wood container texture first
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, wood_texture);
+            self.wood_texture.bind(0);
 
             // Bind smile texture second
-            gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, smile_texture);
+            self.smile_texture.bind(1);
 
             // Bind vertex array
-            gl::BindVertexArray(vao);
+            self.vao.bind();
 
             for (i, pos) in CUBE_POSITIONS.iter().enumerate() {
                 // Create model transform
@@ -363,7 +251,7 @@ fn main() {
 
                 // Set model transform
                 gl::UniformMatrix4fv(
-                    model_location,
+                    self.model_location,
                     1,
                     gl::FALSE,
                     nalgebra_glm::value_ptr(&model).as_ptr() as *const GLfloat,
@@ -373,8 +261,12 @@ fn main() {
                 gl::DrawElements(gl::TRIANGLES, 36, gl::UNSIGNED_INT, 0 as *const c_void);
             }
         }
-
-        window.swap_buffers();
-        glfw.poll_events();
     }
 }
+
+fn main() {
+    Runner::new("Getting Started")
+        .capture_cursor(true)
+        .run::<CameraDemo>()
+        .unwrap();
+}
@@ -5,12 +5,12 @@ extern crate glfw;
 
 use std::{f32::consts::PI, ffi::c_void};
 
-use glfw::{
-    Action, Context, CursorMode, GlfwReceiver, Key, OpenGlProfileHint, PWindow, WindowEvent,
-    WindowHint, WindowMode,
+use glfw::{Action, Key, PWindow, WindowEvent};
+use learn_opengl::{
+    objects::{Buffer, VertexArray},
+    App, Camera, CameraController, GlContext, LightList, MovementInput, PointLight, Runner, Shader,
+    Spotlight, Texture2D,
 };
-use image::io::Reader as ImageReader;
-use learn_opengl::{Camera, Shader};
 use nalgebra_glm as glm;
 
 #[rustfmt::skip]
@@ -71,14 +71,13 @@ const CUBE_POSITIONS: [glm::Vec3; 10] = [
     glm::Vec3::new(-1.3, 1.0, -1.5),
 ];
 
-const POINT_LIGHT_COUNT: usize = 4;
-const POINT_LIGHT_POSITIONS: [glm::Vec3; POINT_LIGHT_COUNT] = [
+const POINT_LIGHT_POSITIONS: [glm::Vec3; 4] = [
     glm::Vec3::new(0.7, 0.2, 2.0),
     glm::Vec3::new(2.3, -3.3, -4.0),
     glm::Vec3::new(0.0, 0.0, -3.0),
     glm::Vec3::new(-4.0, 2.0, -8.0),
 ];
-const POINT_LIGHT_COLORS: [glm::Vec3; POINT_LIGHT_COUNT] = [
+const POINT_LIGHT_COLORS: [glm::Vec3; 4] = [
     glm::Vec3::new(1.0, 0.0, 0.0),
     glm::Vec3::new(0.0, 1.0, 0.0),
     glm::Vec3::new(0.0, 0.0, 1.0),
@@ -88,391 +87,233 @@ const POINT_LIGHT_COLORS: [glm::Vec3; POINT_LIGHT_COUNT] = [
 const MOUSE_SENSITIVITY: f32 = 0.2;
 const CAMERA_SPEED: f32 = 5.0;
 
-unsafe fn load_texture<P>(file_path: P) -> u32
-where
-    P: AsRef<std::path::Path>,
-{
-    let img = ImageReader::open(file_path)
-        .unwrap()
-        .decode()
-        .unwrap()
-        .flipv();
-    let img_bytes = img.as_bytes();
-
-    // Create texture
-    let mut texture = 0;
-    gl::GenTextures(1, &mut texture);
-    gl::BindTexture(gl::TEXTURE_2D, texture);
-
-    // Texture parameters
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-    // Set texture pixel data
-    gl::TexImage2D(
-        gl::TEXTURE_2D,
-        0,
-        gl::RGB as i32,
-        img.width() as i32,
-        img.height() as i32,
-        0,
-        gl::RGBA,
-        gl::UNSIGNED_BYTE,
-        img_bytes.as_ptr() as *const c_void,
-    );
-    gl::GenerateMipmap(gl::TEXTURE_2D);
-
-    texture
+struct MultipleLights {
+    light_list: LightList,
+    light_shader: Shader,
+    cube_shader: Shader,
+    _cube_vbo: Buffer,
+    light_vao: VertexArray,
+    cube_vao: VertexArray,
+    diffuse_map: Texture2D,
+    specular_map: Texture2D,
+    camera: Camera,
+    flash_on: i32,
+    controller: CameraController,
+    time: f64,
 }
 
-fn process_events(
-    events: &GlfwReceiver<(f64, WindowEvent)>,
-    window: &mut PWindow,
-    delta_time: f32,
-    last_mouse_x: &mut f32,
-    last_mouse_y: &mut f32,
-    camera: &mut Camera,
-    flash_on: &mut i32,
-) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            WindowEvent::FramebufferSize(w, h) => unsafe {
-                gl::Viewport(0, 0, w, h);
-            },
-            WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-            WindowEvent::Key(Key::F, _, Action::Press, _) => *flash_on = (*flash_on + 1) % 2,
-            WindowEvent::CursorPos(x_pos, y_pos) => {
-                let dx = (x_pos as f32 - *last_mouse_x) * MOUSE_SENSITIVITY;
-                let dy = -(y_pos as f32 - *last_mouse_y) * MOUSE_SENSITIVITY;
-
-                *last_mouse_x = x_pos as f32;
-                *last_mouse_y = y_pos as f32;
-
-                camera.look_around(dx, dy);
-            }
-            WindowEvent::Scroll(_, offset) => camera.zoom(offset as f32),
-            _ => {}
+impl App for MultipleLights {
+    fn init(context: &GlContext, window: &mut PWindow) -> Self {
+        // Enable OpenGL features
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
         }
-    }
 
-    if window.get_key(glfw::Key::W) == glfw::Action::Press {
-        camera.move_front(CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::S) == glfw::Action::Press {
-        camera.move_front(-CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::A) == glfw::Action::Press {
-        camera.move_side(-CAMERA_SPEED * delta_time);
-    } else if window.get_key(glfw::Key::D) == glfw::Action::Press {
-        camera.move_side(CAMERA_SPEED * delta_time);
-    }
-}
+        // Texture units 0 and 1 hold the material maps
+        let light_list = LightList::new(context, 0, 2);
+
+        let (light_shader, cube_shader) = {
+            let light_shader = Shader::new(
+                context,
+                "shaders/section_17_exercise_1/cube_vert.glsl",
+                "shaders/section_17_exercise_1/light_frag.glsl",
+            )
+            .unwrap();
+            let cube_shader = Shader::with_defines(
+                context,
+                "shaders/section_17_exercise_1/cube_vert.glsl",
+                "shaders/section_17_exercise_1/cube_frag.glsl",
+                light_list.defines(),
+            )
+            .unwrap();
+            cube_shader.validate_uniforms(cube_uniform_names()).unwrap();
+
+            (light_shader, cube_shader)
+        };
+
+        let cube_vbo = unsafe {
+            let vbo = Buffer::new(context);
+            vbo.bind(gl::ARRAY_BUFFER);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&CUBE_VERTICES) as isize,
+                CUBE_VERTICES.as_ptr() as *const c_void,
+                gl::STATIC_DRAW,
+            );
 
-fn main() {
-    // Initialize GLFW
-    let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
-    glfw.window_hint(WindowHint::ContextVersion(3, 3));
-    glfw.window_hint(WindowHint::OpenGlProfile(OpenGlProfileHint::Core));
-    #[cfg(target_os = "macos")]
-    glfw.window_hint(WindowHint::OpenGlForwardCompat(true));
-
-    // Create window
-    let (mut window, events) = glfw
-        .create_window(800, 600, "Lighting", WindowMode::Windowed)
-        .unwrap();
-    window.make_current();
-    window.set_framebuffer_size_polling(true);
-    window.set_key_polling(true);
-    window.set_cursor_pos_polling(true);
-    window.set_cursor_mode(CursorMode::Disabled);
-    window.set_scroll_polling(true);
-
-    // Load OpenGL function pointers
-    gl::load_with(|s| window.get_proc_address(s));
-
-    // Enable OpenGL features
-    unsafe {
-        gl::Enable(gl::DEPTH_TEST);
-    }
+            vbo
+        };
 
-    let (light_shader, cube_shader) = unsafe {
-        let light_shader = Shader::new(
-            "shaders/section_17_exercise_1/cube_vert.glsl",
-            "shaders/section_17_exercise_1/light_frag.glsl",
-        )
-        .unwrap();
-        let cube_shader = Shader::new(
-            "shaders/section_17_exercise_1/cube_vert.glsl",
-            "shaders/section_17_exercise_1/cube_frag.glsl",
-        )
-        .unwrap();
+        let light_vao = unsafe {
+            let vao = VertexArray::new(context);
+            vao.bind();
 
-        (light_shader, cube_shader)
-    };
-
-    let cube_vbo = unsafe {
-        let mut vbo = 0;
-
-        gl::GenBuffers(1, &mut vbo);
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            std::mem::size_of_val(&CUBE_VERTICES) as isize,
-            CUBE_VERTICES.as_ptr() as *const c_void,
-            gl::STATIC_DRAW,
-        );
-
-        vbo
-    };
-
-    let light_vao = unsafe {
-        let mut vao = 0;
-        gl::GenVertexArrays(1, &mut vao);
-        gl::BindVertexArray(vao);
-
-        gl::BindBuffer(gl::ARRAY_BUFFER, cube_vbo);
-        let f32_size = std::mem::size_of::<f32>() as i32;
-
-        // Position coords
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * f32_size, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-
-        gl::BindVertexArray(0);
-        vao
-    };
-
-    let cube_vao = unsafe {
-        let mut vao = 0;
-        gl::GenVertexArrays(1, &mut vao);
-        gl::BindVertexArray(vao);
-
-        gl::BindBuffer(gl::ARRAY_BUFFER, cube_vbo);
-        let f32_size = std::mem::size_of::<f32>() as i32;
-
-        // Position coords
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * f32_size, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-
-        // Surface normal coords
-        gl::VertexAttribPointer(
-            1,
-            3,
-            gl::FLOAT,
-            gl::FALSE,
-            8 * f32_size,
-            (3 * f32_size) as *const c_void,
-        );
-        gl::EnableVertexAttribArray(1);
-
-        // Texture coords
-        gl::VertexAttribPointer(
-            2,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            8 * f32_size,
-            (6 * f32_size) as *const c_void,
-        );
-        gl::EnableVertexAttribArray(2);
-
-        gl::BindVertexArray(0);
-        vao
-    };
-
-    let diffuse_map = unsafe { load_texture("assets/textures/container2.png") };
-    let specular_map = unsafe { load_texture("assets/textures/container2_specular.png") };
-
-    let mut camera = Camera::new(glm::vec3(0.0, 1.0, 3.0), 60.0, -90.0, -10.0);
-    let mut flash_on = 1;
-
-    let mut last_mouse_x = 0.0;
-    let mut last_mouse_y = 0.0;
-
-    let mut last_frame = glfw.get_time();
-
-    while !window.should_close() {
-        let now = glfw.get_time();
-        let delta_time = (now - last_frame) as f32;
-        last_frame = now;
-
-        // Process window events
-        process_events(
-            &events,
-            &mut window,
-            delta_time,
-            &mut last_mouse_x,
-            &mut last_mouse_y,
-            &mut camera,
-            &mut flash_on,
-        );
-
-        let view = camera.look_at_matrix();
-
-        let window_size = window.get_size();
-        let projection = glm::perspective(
-            window_size.0 as f32 / window_size.1 as f32,
-            camera.fov().to_radians(),
-            0.1,
-            100.0,
-        );
+            cube_vbo.bind(gl::ARRAY_BUFFER);
+            let f32_size = std::mem::size_of::<f32>() as i32;
 
-        // Light cube uniforms
-        unsafe {
-            light_shader.use_program();
+            // Position coords
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * f32_size, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+
+            gl::BindVertexArray(0);
+            vao
+        };
 
-            gl::UniformMatrix4fv(
-                light_shader.get_uniform_location("view"),
+        let cube_vao = unsafe {
+            let vao = VertexArray::new(context);
+            vao.bind();
+
+            cube_vbo.bind(gl::ARRAY_BUFFER);
+            let f32_size = std::mem::size_of::<f32>() as i32;
+
+            // Position coords
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * f32_size, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+
+            // Surface normal coords
+            gl::VertexAttribPointer(
                 1,
+                3,
+                gl::FLOAT,
                 gl::FALSE,
-                glm::value_ptr(&view).as_ptr(),
+                8 * f32_size,
+                (3 * f32_size) as *const c_void,
             );
-            gl::UniformMatrix4fv(
-                light_shader.get_uniform_location("projection"),
-                1,
+            gl::EnableVertexAttribArray(1);
+
+            // Texture coords
+            gl::VertexAttribPointer(
+                2,
+                2,
+                gl::FLOAT,
                 gl::FALSE,
-                glm::value_ptr(&projection).as_ptr(),
+                8 * f32_size,
+                (6 * f32_size) as *const c_void,
             );
+            gl::EnableVertexAttribArray(2);
+
+            gl::BindVertexArray(0);
+            vao
+        };
+
+        let diffuse_map = Texture2D::from_file(context, "assets/textures/container2.png").unwrap();
+        let specular_map =
+            Texture2D::from_file(context, "assets/textures/container2_specular.png").unwrap();
+
+        let mut camera = Camera::new(glm::vec3(0.0, 1.0, 3.0), 60.0, -90.0, -10.0);
+        let (width, height) = window.get_framebuffer_size();
+        camera.resize(width, height);
+        let flash_on = 1;
+
+        Self {
+            light_list,
+            light_shader,
+            cube_shader,
+            _cube_vbo: cube_vbo,
+            light_vao,
+            cube_vao,
+            diffuse_map,
+            specular_map,
+            camera,
+            flash_on,
+            controller: CameraController::new()
+                .speed(CAMERA_SPEED)
+                .mouse_sensitivity(MOUSE_SENSITIVITY),
+            time: 0.0,
         }
+    }
 
-        // Normal cube uniforms
-        unsafe {
-            cube_shader.use_program();
+    fn on_event(&mut self, _window: &mut PWindow, event: &WindowEvent) {
+        match *event {
+            WindowEvent::FramebufferSize(width, height) => self.camera.resize(width, height),
+            WindowEvent::Key(Key::F, _, Action::Press, _) => {
+                self.flash_on = (self.flash_on + 1) % 2
+            }
+            WindowEvent::CursorPos(x, y) => self.controller.cursor_moved(x, y),
+            WindowEvent::Scroll(_, offset) => self.camera.zoom(offset as f32),
+            _ => {}
+        }
+    }
 
-            // Material
-            gl::Uniform1i(cube_shader.get_uniform_location("material.diffuse"), 0);
-            gl::Uniform1i(cube_shader.get_uniform_location("material.specular"), 1);
-            gl::Uniform1f(cube_shader.get_uniform_location("material.shininess"), 32.0);
+    fn update(&mut self, window: &PWindow, dt: f32) {
+        self.time += dt as f64;
 
-            // Direction light
-            gl::Uniform3fv(
-                cube_shader.get_uniform_location("dirLight.direction"),
-                1,
-                glm::value_ptr(&glm::vec3(-0.2, -1.0, -0.5)).as_ptr(),
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("dirLight.ambient"),
-                0.1,
-                0.1,
-                0.1,
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("dirLight.diffuse"),
-                0.3,
-                0.3,
-                0.3,
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("dirLight.specular"),
-                0.5,
-                0.5,
-                0.5,
-            );
+        self.controller
+            .update(&mut self.camera, &MovementInput::from_window(window), dt);
+    }
 
-            for i in 0..POINT_LIGHT_COUNT {
-                let pos = POINT_LIGHT_POSITIONS[i];
-                let color = POINT_LIGHT_COLORS[i];
+    fn render(&mut self, _context: &GlContext, _window: &PWindow) {
+        let now = self.time;
 
-                gl::Uniform3fv(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].position")),
-                    1,
-                    glm::value_ptr(&pos).as_ptr(),
-                );
-                gl::Uniform3fv(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].ambient")),
-                    1,
-                    glm::value_ptr(&(color * 0.3)).as_ptr(),
-                );
-                gl::Uniform3fv(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].diffuse")),
-                    1,
-                    glm::value_ptr(&(color * 0.8)).as_ptr(),
-                );
-                gl::Uniform3fv(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].specular")),
-                    1,
-                    glm::value_ptr(&color).as_ptr(),
-                );
-                gl::Uniform1f(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].constant")),
-                    1.0,
-                );
-                gl::Uniform1f(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].linear")),
-                    0.07,
-                );
-                gl::Uniform1f(
-                    cube_shader.get_uniform_location(&format!("pointLights[{i}].quadratic")),
-                    0.017,
-                );
-            }
+        let view = self.camera.look_at_matrix();
 
-            // Spotlight
-            gl::Uniform3fv(
-                cube_shader.get_uniform_location("spotlight.position"),
-                1,
-                glm::value_ptr(&camera.position()).as_ptr(),
-            );
-            gl::Uniform3fv(
-                cube_shader.get_uniform_location("spotlight.direction"),
-                1,
-                glm::value_ptr(&camera.front()).as_ptr(),
-            );
-            gl::Uniform1f(
-                cube_shader.get_uniform_location("spotlight.innerCutoff"),
-                (PI / 12.).cos(),
-            );
-            gl::Uniform1f(
-                cube_shader.get_uniform_location("spotlight.outerCutoff"),
-                (PI / 10.).cos(),
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("spotlight.ambient"),
-                0.3,
-                0.3,
-                0.3,
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("spotlight.diffuse"),
-                0.8,
-                0.8,
-                0.8,
-            );
-            gl::Uniform3f(
-                cube_shader.get_uniform_location("spotlight.specular"),
-                1.0,
-                1.0,
-                1.0,
-            );
-            gl::Uniform1f(cube_shader.get_uniform_location("spotlight.constant"), 1.0);
-            gl::Uniform1f(cube_shader.get_uniform_location("spotlight.linear"), 0.07);
-            gl::Uniform1f(
-                cube_shader.get_uniform_location("spotlight.quadratic"),
-                0.017,
-            );
+        let projection = self.camera.projection_matrix();
 
-            gl::Uniform3fv(
-                cube_shader.get_uniform_location("cameraPos"),
-                1,
-                glm::value_ptr(&camera.position()).as_ptr(),
-            );
-            gl::Uniform1i(
-                cube_shader.get_uniform_location("spotlightEnabled"),
-                flash_on,
-            );
+        // Pick up shader edits
+        self.light_shader.reload_if_changed();
+        self.cube_shader.reload_if_changed();
 
-            gl::UniformMatrix4fv(
-                cube_shader.get_uniform_location("view"),
-                1,
-                gl::FALSE,
-                glm::value_ptr(&view).as_ptr(),
-            );
-            gl::UniformMatrix4fv(
-                cube_shader.get_uniform_location("projection"),
-                1,
-                gl::FALSE,
-                glm::value_ptr(&projection).as_ptr(),
-            );
+        // Light cube uniforms
+        self.light_shader.use_program();
+
+        self.light_shader.set_uniform("view", view);
+        self.light_shader.set_uniform("projection", projection);
+
+        // Normal cube uniforms
+        self.cube_shader.use_program();
+
+        // Material
+        self.cube_shader.set_uniform("material.diffuse", 0);
+        self.cube_shader.set_uniform("material.specular", 1);
+        self.cube_shader.set_uniform("material.shininess", 32.0);
+
+        // Direction light
+        self.cube_shader
+            .set_uniform("dirLight.direction", glm::vec3(-0.2, -1.0, -0.5));
+        self.cube_shader
+            .set_uniform("dirLight.ambient", glm::vec3(0.1, 0.1, 0.1));
+        self.cube_shader
+            .set_uniform("dirLight.diffuse", glm::vec3(0.3, 0.3, 0.3));
+        self.cube_shader
+            .set_uniform("dirLight.specular", glm::vec3(0.5, 0.5, 0.5));
+
+        // Point lights and flashlight
+        let point_lights: Vec<PointLight> = POINT_LIGHT_POSITIONS
+            .iter()
+            .zip(POINT_LIGHT_COLORS)
+            .map(|(&position, color)| PointLight {
+                position,
+                ambient: color * 0.3,
+                diffuse: color * 0.8,
+                specular: color,
+                constant: 1.0,
+                linear: 0.07,
+                quadratic: 0.017,
+            })
+            .collect();
+        let mut spotlights = Vec::new();
+        if self.flash_on == 1 {
+            spotlights.push(Spotlight {
+                position: self.camera.position(),
+                direction: self.camera.front(),
+                inner_cutoff: (PI / 12.).cos(),
+                outer_cutoff: (PI / 10.).cos(),
+                ambient: glm::vec3(0.3, 0.3, 0.3),
+                diffuse: glm::vec3(0.8, 0.8, 0.8),
+                specular: glm::vec3(1.0, 1.0, 1.0),
+                constant: 1.0,
+                linear: 0.07,
+                quadratic: 0.017,
+            });
         }
+        self.light_list.update(&point_lights, &spotlights);
+        self.light_list.bind(&self.cube_shader).unwrap();
+
+        self.cube_shader
+            .set_uniform("cameraPos", self.camera.position());
+
+        self.cube_shader.set_uniform("view", view);
+        self.cube_shader.set_uniform("projection", projection);
 
         // Rendering commands
         unsafe {
@@ -480,41 +321,32 @@ fn main() {
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
 
             // Draw point lights
-            light_shader.use_program();
-            gl::BindVertexArray(light_vao);
+            self.light_shader.use_program();
+            self.light_vao.bind();
 
-            for i in 0..POINT_LIGHT_COUNT {
+            for (light_pos, light_color) in POINT_LIGHT_POSITIONS.iter().zip(POINT_LIGHT_COLORS) {
                 // Construct model matrix
                 let mut light_model = glm::Mat4::identity();
-                light_model = glm::translate(&light_model, &POINT_LIGHT_POSITIONS[i]);
+                light_model = glm::translate(&light_model, light_pos);
                 light_model = glm::scale(&light_model, &glm::vec3(0.25, 0.25, 0.25));
 
                 // Set uniforms
-                gl::UniformMatrix4fv(
-                    light_shader.get_uniform_location("model"),
-                    1,
-                    gl::FALSE,
-                    glm::value_ptr(&light_model).as_ptr(),
-                );
-                gl::Uniform3fv(
-                    light_shader.get_uniform_location("lightColor"),
-                    1,
-                    glm::value_ptr(&POINT_LIGHT_COLORS[i]).as_ptr(),
-                );
+                self.light_shader.set_uniform("model", light_model);
+                self.light_shader.set_uniform("lightColor", light_color);
 
                 // Draw the point light
                 gl::DrawArrays(gl::TRIANGLES, 0, 36);
             }
 
             // Draw party cubes
-            cube_shader.use_program();
+            self.cube_shader.use_program();
 
             gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, diffuse_map);
+            gl::BindTexture(gl::TEXTURE_2D, self.diffuse_map.id());
             gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, specular_map);
+            gl::BindTexture(gl::TEXTURE_2D, self.specular_map.id());
 
-            gl::BindVertexArray(cube_vao);
+            self.cube_vao.bind();
 
             for (i, pos) in CUBE_POSITIONS.iter().enumerate() {
                 let angle = 20.0 * i as f32 + now as f32 * 25.0;
@@ -529,20 +361,37 @@ fn main() {
                 );
 
                 // Set uniform value
-                gl::UniformMatrix4fv(
-                    cube_shader.get_uniform_location("model"),
-                    1,
-                    gl::FALSE,
-                    glm::value_ptr(&cube_model).as_ptr(),
-                );
+                self.cube_shader.set_uniform("model", cube_model);
 
                 // Draw the party cube
                 gl::DrawArrays(gl::TRIANGLES, 0, 36);
             }
         }
-
-        // Poll events and swap buffers
-        window.swap_buffers();
-        glfw.poll_events();
     }
 }
+
+fn main() {
+    Runner::new("Lighting")
+        .capture_cursor(true)
+        .run::<MultipleLights>()
+        .unwrap();
+}
+
+/// Every uniform set on the cube shader, so typos fail at startup instead of going unset.
+fn cube_uniform_names() -> [&'static str; 13] {
+    [
+        "material.diffuse",
+        "material.specular",
+        "material.shininess",
+        "dirLight.direction",
+        "dirLight.ambient",
+        "dirLight.diffuse",
+        "dirLight.specular",
+        "pointLightCount",
+        "spotlightCount",
+        "cameraPos",
+        "model",
+        "view",
+        "projection",
+    ]
+}
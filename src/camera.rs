@@ -1,11 +1,44 @@
+use crate::Frustum;
+
+/// The closest an orbiting camera gets to its target.
+const MIN_ORBIT_DISTANCE: f32 = 0.01;
+
+/// How a `Camera` projects the scene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Perspective with the camera's field of view between the near and far planes.
+    Perspective,
+    /// Parallel projection showing `height` world units vertically.
+    Orthographic { height: f32 },
+    /// Perspective with the far plane at infinity and depth reversed, so the near plane ends up
+    /// at 1 and infinity at 0. Keeps far away depth precise, but only with
+    /// `glClipControl(GL_LOWER_LEFT, GL_ZERO_TO_ONE)`, depth cleared to 0 and `GL_GREATER`.
+    InfiniteReversedZ,
+}
+
 pub struct Camera {
     position: nalgebra_glm::Vec3,
     front: nalgebra_glm::Vec3,
     up: nalgebra_glm::Vec3,
     fov: f32,
+    // Projection
+    projection: Projection,
+    aspect: f32,
+    near: f32,
+    far: f32,
     // Euler Angles
     yaw: f32,
     pitch: f32,
+    // Replaces the Euler angles in free-fly mode
+    orientation: Option<nalgebra_glm::Quat>,
+    orbit: Option<Orbit>,
+}
+
+/// Keeps the camera `distance` away from `target`, looking at it.
+#[derive(Clone, Copy)]
+struct Orbit {
+    target: nalgebra_glm::Vec3,
+    distance: f32,
 }
 
 impl Camera {
@@ -15,13 +48,52 @@ impl Camera {
             front: nalgebra_glm::Vec3::zeros(),
             up: nalgebra_glm::Vec3::y(),
             fov,
+            projection: Projection::Perspective,
+            aspect: 4.0 / 3.0,
+            near: 0.1,
+            far: 100.0,
             yaw,
             pitch,
+            orientation: None,
+            orbit: None,
         };
         cam.update_vectors();
         cam
     }
 
+    /// A camera in free-fly mode, which can roll and look straight up or down without the
+    /// world's up getting in the way. Angles are in degrees, like in `new`.
+    pub fn free_fly(
+        position: nalgebra_glm::Vec3,
+        fov: f32,
+        yaw: f32,
+        pitch: f32,
+        roll: f32,
+    ) -> Self {
+        let mut cam = Self::new(position, fov, yaw, pitch);
+        cam.orientation = Some(euler_to_quat(yaw, pitch, roll));
+        cam.update_vectors();
+        cam
+    }
+
+    /// A camera orbiting `target` from `distance` away, looking in the direction given by `yaw`
+    /// and `pitch`.
+    pub fn orbiting(
+        target: nalgebra_glm::Vec3,
+        distance: f32,
+        fov: f32,
+        yaw: f32,
+        pitch: f32,
+    ) -> Self {
+        let mut cam = Self::new(target, fov, yaw, pitch);
+        cam.orbit = Some(Orbit {
+            target,
+            distance: distance.max(MIN_ORBIT_DISTANCE),
+        });
+        cam.update_vectors();
+        cam
+    }
+
     pub fn position(&self) -> nalgebra_glm::Vec3 {
         self.position
     }
@@ -30,14 +102,167 @@ impl Camera {
         self.front
     }
 
+    /// The camera's own up, which is always +Y outside free-fly mode.
+    pub fn up(&self) -> nalgebra_glm::Vec3 {
+        self.up
+    }
+
     pub fn fov(&self) -> f32 {
         self.fov
     }
 
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    /// Width over height of the viewport, 4:3 until it's set.
+    pub fn aspect(&self) -> f32 {
+        self.aspect
+    }
+
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect;
+    }
+
+    /// Matches the aspect ratio to a framebuffer of `width` x `height`, e.g. on
+    /// `WindowEvent::FramebufferSize`. Minimized windows report a size of 0 and are ignored.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f32 / height as f32;
+        }
+    }
+
+    pub fn near(&self) -> f32 {
+        self.near
+    }
+
+    /// The far plane, which `Projection::InfiniteReversedZ` ignores.
+    pub fn far(&self) -> f32 {
+        self.far
+    }
+
+    /// Sets the near and far planes, 0.1 and 100 by default.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+    }
+
+    /// Yaw, pitch and roll in degrees. Roll is always 0 outside free-fly mode.
+    pub fn euler_angles(&self) -> (f32, f32, f32) {
+        match self.orientation {
+            Some(orientation) => quat_to_euler(&orientation),
+            None => (self.yaw, self.pitch, 0.0),
+        }
+    }
+
+    /// The rotation from a camera looking down -Z with +Y up to this one.
+    pub fn orientation(&self) -> nalgebra_glm::Quat {
+        self.orientation
+            .unwrap_or_else(|| euler_to_quat(self.yaw, self.pitch, 0.0))
+    }
+
+    pub fn is_free_fly(&self) -> bool {
+        self.orientation.is_some()
+    }
+
+    /// Switches between free-fly mode and the Euler angles. Entering free-fly mode leaves orbit
+    /// mode, and leaving it drops the roll and clamps the pitch again.
+    pub fn set_free_fly(&mut self, free_fly: bool) {
+        match (free_fly, self.orientation) {
+            (true, None) => {
+                self.orientation = Some(euler_to_quat(self.yaw, self.pitch, 0.0));
+                self.orbit = None;
+            }
+            (false, Some(orientation)) => {
+                let (yaw, pitch, _) = quat_to_euler(&orientation);
+                self.yaw = yaw;
+                self.pitch = pitch.clamp(-89.0, 89.0);
+                self.orientation = None;
+            }
+            _ => {}
+        }
+        self.update_vectors();
+    }
+
+    /// The point the camera orbits, if it's in orbit mode.
+    pub fn target(&self) -> Option<nalgebra_glm::Vec3> {
+        self.orbit.map(|orbit| orbit.target)
+    }
+
+    /// How far the camera is from its orbit target, if it's in orbit mode.
+    pub fn distance(&self) -> Option<f32> {
+        self.orbit.map(|orbit| orbit.distance)
+    }
+
+    /// Switches to orbit mode around `target`, turning to face it from where the camera is.
+    pub fn orbit_around(&mut self, target: nalgebra_glm::Vec3) {
+        self.set_free_fly(false);
+        let offset = target - self.position;
+        let distance = offset.norm();
+        if distance > MIN_ORBIT_DISTANCE {
+            self.yaw = offset.z.atan2(offset.x).to_degrees();
+            self.pitch = (offset.y / distance).asin().to_degrees().clamp(-89.0, 89.0);
+        }
+
+        self.orbit = Some(Orbit {
+            target,
+            distance: distance.max(MIN_ORBIT_DISTANCE),
+        });
+        self.update_vectors();
+    }
+
+    /// Leaves orbit mode, keeping the camera where it is.
+    pub fn stop_orbiting(&mut self) {
+        self.orbit = None;
+    }
+
     pub fn look_at_matrix(&self) -> nalgebra_glm::Mat4 {
         nalgebra_glm::look_at(&self.position, &(self.position + self.front), &self.up)
     }
 
+    pub fn projection_matrix(&self) -> nalgebra_glm::Mat4 {
+        match self.projection {
+            Projection::Perspective => {
+                nalgebra_glm::perspective(self.aspect, self.fov.to_radians(), self.near, self.far)
+            }
+            Projection::Orthographic { height } => {
+                let (half_width, half_height) = (height * self.aspect * 0.5, height * 0.5);
+                nalgebra_glm::ortho(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.near,
+                    self.far,
+                )
+            }
+            Projection::InfiniteReversedZ => nalgebra_glm::reversed_infinite_perspective_rh_zo(
+                self.aspect,
+                self.fov.to_radians(),
+                self.near,
+            ),
+        }
+    }
+
+    /// The projection matrix times the view matrix, taking world space straight to clip space.
+    pub fn view_projection(&self) -> nalgebra_glm::Mat4 {
+        self.projection_matrix() * self.look_at_matrix()
+    }
+
+    /// What the camera can see, in world space.
+    pub fn frustum(&self) -> Frustum {
+        match self.projection {
+            Projection::InfiniteReversedZ => {
+                Frustum::from_matrix_zero_to_one(&self.view_projection())
+            }
+            _ => Frustum::from_matrix(&self.view_projection()),
+        }
+    }
+
     pub fn look_at_custom(&self) -> nalgebra_glm::Mat4 {
         let right = self.front.cross(&self.up).normalize();
         let direction = -self.front;
@@ -66,17 +291,107 @@ impl Camera {
         rotation * translate
     }
 
+    /// Moves forward, or dollies toward the target in orbit mode.
     pub fn move_front(&mut self, speed: f32) {
-        self.position += self.front * speed;
+        match self.orbit {
+            Some(_) => self.dolly(speed),
+            None => self.position += self.front * speed,
+        }
     }
 
+    /// Moves sideways, or pans the target along with the camera in orbit mode.
     pub fn move_side(&mut self, speed: f32) {
-        self.position += self.front.cross(&self.up).normalize() * speed;
+        match self.orbit {
+            Some(_) => self.pan(speed, 0.0),
+            None => self.position += self.right() * speed,
+        }
+    }
+
+    /// Moves along the camera's own up in free-fly mode, along +Y otherwise, or pans in orbit
+    /// mode.
+    pub fn move_up(&mut self, speed: f32) {
+        match self.orbit {
+            Some(_) => self.pan(0.0, speed),
+            None => self.position += self.up * speed,
+        }
     }
 
+    /// Turns the camera, or in orbit mode moves it around the target so it keeps facing it. In
+    /// free-fly mode the camera turns around its own axes and pitch isn't clamped.
     pub fn look_around(&mut self, dx: f32, dy: f32) {
-        self.yaw += dx;
-        self.pitch = (self.pitch + dy).clamp(-89.0, 89.0);
+        match &mut self.orientation {
+            Some(orientation) => {
+                let yaw = nalgebra_glm::quat_angle_axis(-dx.to_radians(), &nalgebra_glm::Vec3::y());
+                let pitch =
+                    nalgebra_glm::quat_angle_axis(dy.to_radians(), &nalgebra_glm::Vec3::x());
+                *orientation = nalgebra_glm::quat_normalize(&(*orientation * yaw * pitch));
+            }
+            None => {
+                self.yaw += dx;
+                self.pitch = (self.pitch + dy).clamp(-89.0, 89.0);
+            }
+        }
+        self.update_vectors();
+    }
+
+    /// Rolls the camera clockwise around its front by `angle` degrees. The Euler angles can't
+    /// roll, so this switches to free-fly mode.
+    pub fn roll(&mut self, angle: f32) {
+        self.set_free_fly(true);
+        if let Some(orientation) = &mut self.orientation {
+            let roll = nalgebra_glm::quat_angle_axis(angle.to_radians(), &-nalgebra_glm::Vec3::z());
+            *orientation = nalgebra_glm::quat_normalize(&(*orientation * roll));
+        }
+        self.update_vectors();
+    }
+
+    /// Moves `amount` toward the orbit target without passing it, or forward outside orbit
+    /// mode.
+    pub fn dolly(&mut self, amount: f32) {
+        match &mut self.orbit {
+            Some(orbit) => {
+                orbit.distance = (orbit.distance - amount).max(MIN_ORBIT_DISTANCE);
+                self.update_vectors();
+            }
+            None => self.position += self.front * amount,
+        }
+    }
+
+    /// Moves the camera, and the orbit target with it, in the view plane.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let right = self.right();
+        let offset = right * dx + right.cross(&self.front) * dy;
+
+        self.position += offset;
+        if let Some(orbit) = &mut self.orbit {
+            orbit.target += offset;
+        }
+    }
+
+    /// Orbits the center of the box from `min` to `max`, just far enough away for the whole box
+    /// to fit into the view. Orthographic projections are also resized to fit it, and the clip
+    /// planes are moved out of the way if they would cut off the front or back of the box.
+    pub fn frame_bounds(&mut self, min: nalgebra_glm::Vec3, max: nalgebra_glm::Vec3) {
+        self.set_free_fly(false);
+        let center = (min + max) * 0.5;
+        let radius = (max - min).norm() * 0.5;
+
+        // The box has to fit into the narrower of the vertical and horizontal field of view
+        let half_fov = (self.fov.to_radians() * 0.5).tan();
+        let half_fov = half_fov.min(half_fov * self.aspect).atan();
+        let distance = (radius / half_fov.sin()).max(MIN_ORBIT_DISTANCE);
+
+        if let Projection::Orthographic { height } = &mut self.projection {
+            *height = 2.0 * radius / self.aspect.min(1.0);
+        }
+
+        self.near = self.near.min(distance - radius);
+        self.far = self.far.max(distance + radius);
+
+        self.orbit = Some(Orbit {
+            target: center,
+            distance,
+        });
         self.update_vectors();
     }
 
@@ -86,6 +401,12 @@ impl Camera {
     }
 
     fn update_vectors(&mut self) {
+        if let Some(orientation) = &self.orientation {
+            self.front = nalgebra_glm::quat_rotate_vec3(orientation, &-nalgebra_glm::Vec3::z());
+            self.up = nalgebra_glm::quat_rotate_vec3(orientation, &nalgebra_glm::Vec3::y());
+            return;
+        }
+
         let cos_pitch = self.pitch.to_radians().cos();
         let direction = nalgebra_glm::vec3(
             self.yaw.to_radians().cos() * cos_pitch,
@@ -93,5 +414,44 @@ impl Camera {
             self.yaw.to_radians().sin() * cos_pitch,
         );
         self.front = direction.normalize();
+        self.up = nalgebra_glm::Vec3::y();
+
+        if let Some(orbit) = self.orbit {
+            self.position = orbit.target - self.front * orbit.distance;
+        }
+    }
+
+    fn right(&self) -> nalgebra_glm::Vec3 {
+        self.front.cross(&self.up).normalize()
     }
 }
+
+/// The orientation of a camera with the given Euler angles in degrees. A yaw of -90 looks down
+/// -Z like the default camera, so the yaw rotation about +Y is offset by that.
+fn euler_to_quat(yaw: f32, pitch: f32, roll: f32) -> nalgebra_glm::Quat {
+    let yaw = nalgebra_glm::quat_angle_axis(-(yaw + 90.0).to_radians(), &nalgebra_glm::Vec3::y());
+    let pitch = nalgebra_glm::quat_angle_axis(pitch.to_radians(), &nalgebra_glm::Vec3::x());
+    let roll = nalgebra_glm::quat_angle_axis(roll.to_radians(), &-nalgebra_glm::Vec3::z());
+    yaw * pitch * roll
+}
+
+/// The inverse of `euler_to_quat`. Yaw and roll are ambiguous when looking straight up or down,
+/// in which case all of the turn ends up in the roll.
+fn quat_to_euler(orientation: &nalgebra_glm::Quat) -> (f32, f32, f32) {
+    let front = nalgebra_glm::quat_rotate_vec3(orientation, &-nalgebra_glm::Vec3::z());
+    let up = nalgebra_glm::quat_rotate_vec3(orientation, &nalgebra_glm::Vec3::y());
+
+    let yaw = front.z.atan2(front.x).to_degrees();
+    let pitch = front.y.clamp(-1.0, 1.0).asin().to_degrees();
+
+    // Roll is how far the up vector turned toward the right from where it'd be without it
+    let unrolled = euler_to_quat(yaw, pitch, 0.0);
+    let unrolled_up = nalgebra_glm::quat_rotate_vec3(&unrolled, &nalgebra_glm::Vec3::y());
+    let unrolled_right = nalgebra_glm::quat_rotate_vec3(&unrolled, &nalgebra_glm::Vec3::x());
+    let roll = up
+        .dot(&unrolled_right)
+        .atan2(up.dot(&unrolled_up))
+        .to_degrees();
+
+    (yaw, pitch, roll)
+}
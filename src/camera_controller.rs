@@ -0,0 +1,210 @@
+use nalgebra_glm as glm;
+
+use crate::Camera;
+
+/// Below this speed the camera is considered to have stopped.
+const MIN_SPEED: f32 = 1e-3;
+
+/// The movement keys held during a frame, whichever library reported them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MovementInput {
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub sprint: bool,
+    pub crawl: bool,
+}
+
+impl MovementInput {
+    /// The direction the keys point in relative to the camera, with x to the right, y up and z
+    /// forward, or zero if opposite keys cancel out.
+    pub fn direction(&self) -> glm::Vec3 {
+        let axis = |positive: bool, negative: bool| positive as i32 as f32 - negative as i32 as f32;
+        let direction = glm::vec3(
+            axis(self.right, self.left),
+            axis(self.up, self.down),
+            axis(self.forward, self.backward),
+        );
+
+        if direction == glm::Vec3::zeros() {
+            direction
+        } else {
+            direction.normalize()
+        }
+    }
+}
+
+/// Flies a `Camera` around from key and mouse input. Held keys accelerate the camera up to its
+/// speed, all of them at once so diagonals work, and it glides to a stop with exponential
+/// damping once they're released. Mouse movement can be smoothed out over a short time.
+#[derive(Clone, Debug)]
+pub struct CameraController {
+    speed: f32,
+    acceleration: f32,
+    damping: f32,
+    sprint_multiplier: f32,
+    crawl_multiplier: f32,
+    mouse_sensitivity: f32,
+    mouse_smoothing: f32,
+    // Relative to the camera, like `MovementInput::direction`
+    velocity: glm::Vec3,
+    pending_look: glm::Vec2,
+    look_rate: glm::Vec2,
+    last_cursor: Option<(f64, f64)>,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraController {
+    /// Moves at 2.5 units per second, reaching that speed in a tenth of a second, and turns
+    /// 0.2 degrees per pixel without any smoothing.
+    pub fn new() -> Self {
+        Self {
+            speed: 2.5,
+            acceleration: 25.0,
+            damping: 10.0,
+            sprint_multiplier: 3.0,
+            crawl_multiplier: 0.25,
+            mouse_sensitivity: 0.2,
+            mouse_smoothing: 0.0,
+            velocity: glm::Vec3::zeros(),
+            pending_look: glm::Vec2::zeros(),
+            look_rate: glm::Vec2::zeros(),
+            last_cursor: None,
+        }
+    }
+
+    /// Top speed in units per second.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// How quickly held keys change the velocity, in units per second squared.
+    pub fn acceleration(mut self, acceleration: f32) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// How quickly the camera stops once no keys are held. Each second, the velocity is scaled
+    /// by `e^-damping`.
+    pub fn damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// What the speed is multiplied by while `MovementInput::sprint` is held.
+    pub fn sprint_multiplier(mut self, multiplier: f32) -> Self {
+        self.sprint_multiplier = multiplier;
+        self
+    }
+
+    /// What the speed is multiplied by while `MovementInput::crawl` is held.
+    pub fn crawl_multiplier(mut self, multiplier: f32) -> Self {
+        self.crawl_multiplier = multiplier;
+        self
+    }
+
+    /// Degrees turned per pixel of mouse movement.
+    pub fn mouse_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.mouse_sensitivity = sensitivity;
+        self
+    }
+
+    /// Time constant in seconds that mouse movement is smoothed over, 0 to turn immediately.
+    /// Smoothing delays turns but doesn't change how far the camera ends up turning.
+    pub fn mouse_smoothing(mut self, seconds: f32) -> Self {
+        self.mouse_smoothing = seconds;
+        self
+    }
+
+    /// The current velocity relative to the camera, with x to the right, y up and z forward.
+    pub fn velocity(&self) -> glm::Vec3 {
+        self.velocity
+    }
+
+    /// Stops the camera and drops any mouse movement that wasn't applied yet.
+    pub fn stop(&mut self) {
+        self.velocity = glm::Vec3::zeros();
+        self.pending_look = glm::Vec2::zeros();
+        self.look_rate = glm::Vec2::zeros();
+    }
+
+    /// Queues a turn of `dx` pixels to the right and `dy` pixels up for the next `update`.
+    pub fn look(&mut self, dx: f32, dy: f32) {
+        self.pending_look += glm::vec2(dx, dy);
+    }
+
+    /// Queues a turn from a new cursor position in window coordinates, where y points down.
+    /// The first position only sets where movement is measured from, so the camera doesn't
+    /// jump when the cursor is captured.
+    pub fn cursor_moved(&mut self, x: f64, y: f64) {
+        if let Some((last_x, last_y)) = self.last_cursor {
+            self.look((x - last_x) as f32, (last_y - y) as f32);
+        }
+        self.last_cursor = Some((x, y));
+    }
+
+    /// Advances the camera by `dt` seconds of the held keys and queued mouse movement.
+    pub fn update(&mut self, camera: &mut Camera, input: &MovementInput, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        self.update_velocity(input, dt);
+        camera.move_side(self.velocity.x * dt);
+        camera.move_up(self.velocity.y * dt);
+        camera.move_front(self.velocity.z * dt);
+
+        // Smooth the turn rate rather than the per-frame movement, so the result doesn't
+        // depend on the frame rate
+        let rate = self.pending_look / dt;
+        self.pending_look = glm::Vec2::zeros();
+        self.look_rate = if self.mouse_smoothing > 0.0 {
+            let blend = 1.0 - (-dt / self.mouse_smoothing).exp();
+            self.look_rate + (rate - self.look_rate) * blend
+        } else {
+            rate
+        };
+
+        let turn = self.look_rate * dt * self.mouse_sensitivity;
+        if turn != glm::Vec2::zeros() {
+            camera.look_around(turn.x, turn.y);
+        }
+    }
+
+    fn update_velocity(&mut self, input: &MovementInput, dt: f32) {
+        let direction = input.direction();
+        if direction == glm::Vec3::zeros() {
+            self.velocity *= (-self.damping * dt).exp();
+            if self.velocity.norm() < MIN_SPEED {
+                self.velocity = glm::Vec3::zeros();
+            }
+            return;
+        }
+
+        let mut speed = self.speed;
+        if input.sprint {
+            speed *= self.sprint_multiplier;
+        }
+        if input.crawl {
+            speed *= self.crawl_multiplier;
+        }
+
+        // Accelerate straight toward the target velocity without overshooting it
+        let change = direction * speed - self.velocity;
+        let max_change = self.acceleration * dt;
+        self.velocity += if change.norm() > max_change {
+            change.normalize() * max_change
+        } else {
+            change
+        };
+    }
+}
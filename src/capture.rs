@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+
+use crate::{offscreen, GlContext};
+
+/// Reads back the default framebuffer's back buffer, so what was rendered this frame once it's
+/// called before the buffers are swapped.
+pub fn read_default_framebuffer(_context: &GlContext, width: u32, height: u32) -> RgbaImage {
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        gl::ReadBuffer(gl::BACK);
+    }
+    offscreen::read_pixels(width, height)
+}
+
+/// Saves the default framebuffer to a PNG at `path`.
+pub fn save_screenshot(
+    context: &GlContext,
+    width: u32,
+    height: u32,
+    path: impl AsRef<Path>,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    read_default_framebuffer(context, width, height)
+        .save(path)
+        .map_err(|e| format!("Failed to save {}: {e}", path.display()))
+}
+
+/// Writes frames to numbered PNGs (`frame_000000.png`, `frame_000001.png`, ...) in a directory,
+/// e.g. to turn them into a video with `ffmpeg -framerate 60 -i frame_%06d.png demo.mp4`.
+#[derive(Debug)]
+pub struct FrameRecorder {
+    dir: PathBuf,
+    fps: u32,
+    frames: u32,
+}
+
+impl FrameRecorder {
+    /// Creates `dir` if it doesn't exist yet. Frames are meant to be `1 / fps` seconds apart.
+    pub fn new(dir: impl Into<PathBuf>, fps: u32) -> Result<Self, String> {
+        let dir = dir.into();
+        if fps == 0 {
+            return Err("Recording needs at least 1 frame per second".to_owned());
+        }
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+
+        Ok(Self {
+            dir,
+            fps,
+            frames: 0,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The fixed timestep the recorded scene should advance by between frames.
+    pub fn dt(&self) -> f32 {
+        1.0 / self.fps as f32
+    }
+
+    /// How many frames were saved so far.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Saves `image` as the next frame and returns its path.
+    pub fn save_frame(&mut self, image: &RgbaImage) -> Result<PathBuf, String> {
+        let path = self.dir.join(format!("frame_{:06}.png", self.frames));
+        image
+            .save(&path)
+            .map_err(|e| format!("Failed to save {}: {e}", path.display()))?;
+        self.frames += 1;
+        Ok(path)
+    }
+}
@@ -0,0 +1,47 @@
+//! The one place the crate's GL safety contract lives.
+//!
+//! OpenGL calls are only sound on a thread with a current context whose function pointers
+//! have been loaded. A `GlContext` stands for exactly that: creating one is `unsafe`, and
+//! the caller promises the context stays current on this thread for as long as the token, and
+//! anything created from it, is alive.
+//!
+//! Every GL object in the crate is created from a `&GlContext` and, like the token, is neither
+//! `Send` nor `Sync`. Holding one of them is therefore proof that its context is current, which
+//! is what lets their methods be safe functions.
+
+use std::{ffi::c_void, marker::PhantomData};
+
+/// Stands for an OpenGL context that is current on this thread with its functions loaded. The
+/// module docs of `context.rs` describe the safety contract it carries.
+#[derive(Debug)]
+pub struct GlContext {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl GlContext {
+    /// Loads the GL functions with `loader`, usually `window.get_proc_address`.
+    ///
+    /// # Safety
+    ///
+    /// The context `loader` resolves functions for has to be current on this thread, and stay
+    /// current and alive until the returned token and every object created from it are dropped.
+    pub unsafe fn load_with<F>(loader: F) -> Self
+    where
+        F: FnMut(&'static str) -> *const c_void,
+    {
+        gl::load_with(loader);
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+
+    /// The OpenGL version of the context as `(major, minor)`.
+    pub fn version(&self) -> (i32, i32) {
+        let (mut major, mut minor) = (0, 0);
+        unsafe {
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+        }
+        (major, minor)
+    }
+}
@@ -0,0 +1,136 @@
+use nalgebra_glm as glm;
+
+/// A plane of the points `p` where `normal.dot(p) + distance == 0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: glm::Vec3,
+    pub distance: f32,
+}
+
+impl Plane {
+    /// The plane `coefficients.x * x + coefficients.y * y + coefficients.z * z +
+    /// coefficients.w = 0`, scaled so the normal has unit length. Planes without a normal,
+    /// like the far plane of an infinite projection, are kept as they are.
+    pub fn from_coefficients(coefficients: glm::Vec4) -> Self {
+        let normal = coefficients.xyz();
+        let length = normal.norm();
+        let scale = if length > f32::EPSILON { length } else { 1.0 };
+
+        Self {
+            normal: normal / scale,
+            distance: coefficients.w / scale,
+        }
+    }
+
+    /// How far `point` is in front of the plane, negative when it's behind it.
+    pub fn signed_distance(&self, point: &glm::Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: glm::Vec3, max: glm::Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest box around `points`, or `None` if there are none.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a glm::Vec3>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(match bounds {
+                Some(Aabb { min, max }) => Aabb::new(min.inf(point), max.sup(point)),
+                None => Aabb::new(*point, *point),
+            })
+        })
+    }
+
+    pub fn center(&self) -> glm::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Half the size of the box along each axis.
+    pub fn extents(&self) -> glm::Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// The box around this one after it's transformed by `matrix`, which is usually larger
+    /// than the transformed box itself.
+    pub fn transformed(&self, matrix: &glm::Mat4) -> Self {
+        let center = (matrix * self.center().push(1.0)).xyz();
+        let extents = self.extents();
+        let rotation = matrix.fixed_view::<3, 3>(0, 0).abs();
+        let extents = rotation * extents;
+
+        Self::new(center - extents, center + extents)
+    }
+}
+
+/// The six planes bounding what a camera can see, with their normals pointing inward.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes from a view-projection matrix whose clip space depth goes from -w to
+    /// w, OpenGL's default. Passing `view_projection * model` gives the frustum in the model's
+    /// own space instead of world space.
+    pub fn from_matrix(matrix: &glm::Mat4) -> Self {
+        let row = |i: usize| matrix.row(i).transpose();
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        Self::from_rows([w + x, w - x, w + y, w - y, w + z, w - z])
+    }
+
+    /// Like `from_matrix`, for reversed-Z projections whose clip space depth goes from w at
+    /// the near plane to 0 at the far plane, such as `Projection::InfiniteReversedZ`. Planes
+    /// at infinity never cull anything.
+    pub fn from_matrix_zero_to_one(matrix: &glm::Mat4) -> Self {
+        let row = |i: usize| matrix.row(i).transpose();
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        Self::from_rows([w + x, w - x, w + y, w - y, w - z, z])
+    }
+
+    fn from_rows(rows: [glm::Vec4; 6]) -> Self {
+        Self {
+            planes: rows.map(Plane::from_coefficients),
+        }
+    }
+
+    pub fn contains_point(&self, point: &glm::Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Whether any part of the sphere might be visible. Spheres just outside a corner of the
+    /// frustum pass too, which is fine for culling.
+    pub fn intersects_sphere(&self, center: &glm::Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(center) >= -radius)
+    }
+
+    /// Whether any part of the box might be visible, with the same false positives near the
+    /// corners as `intersects_sphere`.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // The corner furthest along the normal is the last one to leave the plane
+            let corner =
+                plane.normal.zip_zip_map(
+                    &aabb.min,
+                    &aabb.max,
+                    |n, min, max| if n >= 0.0 { max } else { min },
+                );
+            plane.signed_distance(&corner) >= 0.0
+        })
+    }
+}
@@ -1,6 +1,37 @@
+mod app;
 mod camera;
+mod camera_controller;
+mod capture;
+mod context;
+mod culling;
+mod light_list;
 mod mesh;
+mod model;
+mod obj;
+pub mod objects;
+mod offscreen;
 mod shader;
+mod std140;
+mod texture;
+mod uniform;
+mod uniform_buffer;
 
-pub use camera::Camera;
-pub use shader::Shader;
+pub use app::{App, HeadlessBackend, Runner};
+pub use camera::{Camera, Projection};
+pub use camera_controller::{CameraController, MovementInput};
+pub use capture::{read_default_framebuffer, save_screenshot, FrameRecorder};
+pub use context::GlContext;
+pub use culling::{Aabb, Frustum, Plane};
+pub use light_list::{LightList, LightListBackend, PointLight, Spotlight};
+pub use mesh::{Mesh, MeshTexture, TextureType, Vertex};
+pub use model::{Model, ModelError};
+pub use obj::{ObjData, ObjError, ObjMaterial, ObjMesh};
+pub use offscreen::OffscreenTarget;
+pub use shader::{
+    ActiveAttribute, ActiveUniform, CompileFailure, ComputeShader, MissingUniformPolicy, Shader,
+    ShaderBuilder, ShaderDiagnostic, ShaderError, ShaderStage,
+};
+pub use std140::Std140;
+pub use texture::{Texture2D, Texture2DBuilder, TextureError, TextureFilter, TextureWrap};
+pub use uniform::{Uniform, UniformArrayElement};
+pub use uniform_buffer::UniformBuffer;
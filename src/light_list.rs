@@ -0,0 +1,257 @@
+use std::ffi::c_void;
+
+use gl::types::GLenum;
+use nalgebra_glm as glm;
+
+use crate::{
+    objects::{Buffer, Texture},
+    GlContext, Shader, ShaderError,
+};
+
+/// A point light as laid out by `shaders/common/light_types.glsl`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: glm::Vec3,
+    pub ambient: glm::Vec3,
+    pub diffuse: glm::Vec3,
+    pub specular: glm::Vec3,
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl PointLight {
+    /// Packs the light into the four `vec4`s `getPointLight` in `light_list.glsl` reads.
+    fn texels(&self) -> [[f32; 4]; 4] {
+        [
+            vec4(self.position, self.constant),
+            vec4(self.ambient, self.linear),
+            vec4(self.diffuse, self.quadratic),
+            vec4(self.specular, 0.0),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spotlight {
+    pub position: glm::Vec3,
+    pub direction: glm::Vec3,
+    /// Cosine of the angle where the light starts fading out.
+    pub inner_cutoff: f32,
+    /// Cosine of the angle where the light is gone.
+    pub outer_cutoff: f32,
+    pub ambient: glm::Vec3,
+    pub diffuse: glm::Vec3,
+    pub specular: glm::Vec3,
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Spotlight {
+    /// Packs the light into the five `vec4`s `getSpotlight` in `light_list.glsl` reads.
+    fn texels(&self) -> [[f32; 4]; 5] {
+        [
+            vec4(self.position, self.constant),
+            vec4(self.direction, self.linear),
+            vec4(self.ambient, self.quadratic),
+            vec4(self.diffuse, self.inner_cutoff),
+            vec4(self.specular, self.outer_cutoff),
+        ]
+    }
+}
+
+fn vec4(xyz: glm::Vec3, w: f32) -> [f32; 4] {
+    [xyz.x, xyz.y, xyz.z, w]
+}
+
+/// Where a `LightList` keeps its lights on the GPU.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightListBackend {
+    /// Shader storage buffers, which need OpenGL 4.3.
+    StorageBuffer,
+    /// `samplerBuffer` textures, available from OpenGL 3.1.
+    TextureBuffer,
+}
+
+impl LightListBackend {
+    /// Shader storage buffers if the current context supports them, buffer textures otherwise.
+    pub fn detect(context: &GlContext) -> Self {
+        if context.version() >= (4, 3) {
+            LightListBackend::StorageBuffer
+        } else {
+            LightListBackend::TextureBuffer
+        }
+    }
+}
+
+/// Any number of point lights and spotlights for shaders that include
+/// `shaders/common/light_list.glsl`. Those shaders have to be built with `defines()`.
+pub struct LightList {
+    backend: LightListBackend,
+    first_binding: u32,
+    first_texture_unit: u32,
+    point_light_count: usize,
+    spotlight_count: usize,
+    point_light_buffer: Buffer,
+    spotlight_buffer: Buffer,
+    /// Buffer textures viewing the buffers, only used by the texture buffer backend.
+    point_light_texture: Option<Texture>,
+    spotlight_texture: Option<Texture>,
+}
+
+impl LightList {
+    /// Creates an empty list with the best backend for the current context. The point and
+    /// spotlight buffers go to storage buffer binding points `first_binding` and
+    /// `first_binding + 1`, or with the texture buffer backend to texture units
+    /// `first_texture_unit` and `first_texture_unit + 1`. `Mesh::draw` binds material
+    /// textures from unit 0 up, so the texture units have to come after those.
+    pub fn new(context: &GlContext, first_binding: u32, first_texture_unit: u32) -> Self {
+        Self::with_backend(
+            context,
+            LightListBackend::detect(context),
+            first_binding,
+            first_texture_unit,
+        )
+    }
+
+    pub fn with_backend(
+        context: &GlContext,
+        backend: LightListBackend,
+        first_binding: u32,
+        first_texture_unit: u32,
+    ) -> Self {
+        let texture =
+            || (backend == LightListBackend::TextureBuffer).then(|| Texture::new(context));
+
+        let mut list = Self {
+            backend,
+            first_binding,
+            first_texture_unit,
+            point_light_count: 0,
+            spotlight_count: 0,
+            point_light_buffer: Buffer::new(context),
+            spotlight_buffer: Buffer::new(context),
+            point_light_texture: texture(),
+            spotlight_texture: texture(),
+        };
+        list.update(&[], &[]);
+        list
+    }
+
+    pub fn backend(&self) -> LightListBackend {
+        self.backend
+    }
+
+    /// Defines selecting the matching code path in `light_list.glsl`.
+    pub fn defines(&self) -> &'static [(&'static str, &'static str)] {
+        match self.backend {
+            LightListBackend::StorageBuffer => &[("LIGHT_LIST_SSBO", "1")],
+            LightListBackend::TextureBuffer => &[],
+        }
+    }
+
+    pub fn point_light_count(&self) -> usize {
+        self.point_light_count
+    }
+
+    pub fn spotlight_count(&self) -> usize {
+        self.spotlight_count
+    }
+
+    /// Replaces the lights in the list. The buffers are reallocated every time, so lights can
+    /// come and go between frames.
+    pub fn update(&mut self, point_lights: &[PointLight], spotlights: &[Spotlight]) {
+        let point_texels: Vec<[f32; 4]> = point_lights.iter().flat_map(|l| l.texels()).collect();
+        let spot_texels: Vec<[f32; 4]> = spotlights.iter().flat_map(|l| l.texels()).collect();
+
+        self.upload(
+            &self.point_light_buffer,
+            self.point_light_texture.as_ref(),
+            &point_texels,
+        );
+        self.upload(
+            &self.spotlight_buffer,
+            self.spotlight_texture.as_ref(),
+            &spot_texels,
+        );
+        self.point_light_count = point_lights.len();
+        self.spotlight_count = spotlights.len();
+    }
+
+    /// Binds the buffers and sets the light counts on `shader`, which uses its program.
+    pub fn bind(&self, shader: &Shader) -> Result<(), ShaderError> {
+        shader.use_program();
+        match self.backend {
+            LightListBackend::StorageBuffer => {
+                let (point_binding, spot_binding) = (self.first_binding, self.first_binding + 1);
+                shader.bind_storage_block("PointLightBuffer", point_binding)?;
+                shader.bind_storage_block("SpotlightBuffer", spot_binding)?;
+                unsafe {
+                    gl::BindBufferBase(
+                        gl::SHADER_STORAGE_BUFFER,
+                        point_binding,
+                        self.point_light_buffer.id(),
+                    );
+                    gl::BindBufferBase(
+                        gl::SHADER_STORAGE_BUFFER,
+                        spot_binding,
+                        self.spotlight_buffer.id(),
+                    );
+                }
+            }
+            LightListBackend::TextureBuffer => {
+                let (point_unit, spot_unit) =
+                    (self.first_texture_unit, self.first_texture_unit + 1);
+                let textures = [&self.point_light_texture, &self.spotlight_texture];
+                for (texture, unit) in textures.into_iter().zip([point_unit, spot_unit]) {
+                    unsafe { gl::ActiveTexture(gl::TEXTURE0 + unit) };
+                    if let Some(texture) = texture {
+                        texture.bind(gl::TEXTURE_BUFFER);
+                    }
+                }
+                unsafe { gl::ActiveTexture(gl::TEXTURE0) };
+
+                shader.try_set_uniform("pointLightData", point_unit as i32)?;
+                shader.try_set_uniform("spotlightData", spot_unit as i32)?;
+            }
+        }
+
+        shader.try_set_uniform("pointLightCount", self.point_light_count as i32)?;
+        shader.try_set_uniform("spotlightCount", self.spotlight_count as i32)?;
+        Ok(())
+    }
+
+    fn upload(&self, buffer: &Buffer, texture: Option<&Texture>, texels: &[[f32; 4]]) {
+        let target = self.buffer_target();
+
+        // Empty buffers can't back a buffer texture, so there's always room for one texel
+        let size = std::mem::size_of_val(texels).max(std::mem::size_of::<[f32; 4]>());
+        buffer.bind(target);
+        unsafe {
+            gl::BufferData(target, size as isize, std::ptr::null(), gl::DYNAMIC_DRAW);
+            gl::BufferSubData(
+                target,
+                0,
+                std::mem::size_of_val(texels) as isize,
+                texels.as_ptr() as *const c_void,
+            );
+            gl::BindBuffer(target, 0);
+        }
+
+        if let Some(texture) = texture {
+            texture.bind(gl::TEXTURE_BUFFER);
+            unsafe {
+                gl::TexBuffer(gl::TEXTURE_BUFFER, gl::RGBA32F, buffer.id());
+                gl::BindTexture(gl::TEXTURE_BUFFER, 0);
+            }
+        }
+    }
+
+    fn buffer_target(&self) -> GLenum {
+        match self.backend {
+            LightListBackend::StorageBuffer => gl::SHADER_STORAGE_BUFFER,
+            LightListBackend::TextureBuffer => gl::TEXTURE_BUFFER,
+        }
+    }
+}
@@ -1,131 +1,226 @@
-use std::os::raw::c_void;
+use std::{ffi::c_void, mem::offset_of, rc::Rc};
 
-use gl::types::GLuint;
 use nalgebra_glm as glm;
 
-use crate::Shader;
+use crate::{
+    objects::{Buffer, VertexArray},
+    Aabb, GlContext, Shader, Texture2D,
+};
 
-#[repr(packed)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vertex {
-    position: glm::Vec3,
-    normal: glm::Vec3,
-    texture_coords: glm::Vec2,
+    pub position: glm::Vec3,
+    pub normal: glm::Vec3,
+    pub texture_coords: glm::Vec2,
 }
 
+impl Vertex {
+    pub fn new(position: glm::Vec3, normal: glm::Vec3, texture_coords: glm::Vec2) -> Self {
+        Self {
+            position,
+            normal,
+            texture_coords,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum TextureType {
     Diffuse,
     Specular,
 }
 
-pub struct Texture {
-    id: GLuint,
-    texture_type: TextureType,
+impl TextureType {
+    /// Prefix of the sampler uniform this texture is bound to, e.g. `material.texture_diffuse1`.
+    pub fn uniform_prefix(&self) -> &'static str {
+        match self {
+            TextureType::Diffuse => "texture_diffuse",
+            TextureType::Specular => "texture_specular",
+        }
+    }
+}
+
+/// A texture a mesh samples, shared with the other meshes of its model that use it too.
+#[derive(Clone)]
+pub struct MeshTexture {
+    pub texture: Rc<Texture2D>,
+    pub texture_type: TextureType,
+}
+
+impl MeshTexture {
+    pub fn new(texture: Rc<Texture2D>, texture_type: TextureType) -> Self {
+        Self {
+            texture,
+            texture_type,
+        }
+    }
 }
 
 pub struct Mesh {
-    pub vertices: Vec<Vertex>,
-    pub indices: Vec<usize>,
-    pub textures: Vec<Texture>,
-    vao: GLuint,
-    vbo: GLuint,
-    ebo: GLuint,
+    /// Copies of what was uploaded, private so they can't go out of sync with the buffers.
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    pub textures: Vec<MeshTexture>,
+    /// Set as `material.shininess` when drawing, if the source material specified one.
+    pub shininess: Option<f32>,
+    bounds: Aabb,
+    vao: VertexArray,
+    _vbo: Buffer,
+    _ebo: Buffer,
 }
 
 impl Mesh {
-    pub unsafe fn new(vertices: Vec<Vertex>, indices: Vec<usize>, textures: Vec<Texture>) -> Self {
-        let (mut vao, mut vbo, mut ebo) = (0, 0, 0);
+    /// Uploads the vertices and indices, drawn as a triangle list.
+    ///
+    /// # Panics
+    ///
+    /// If an index is out of range for `vertices`, since drawing would read past the end of
+    /// the vertex buffer.
+    pub fn new(
+        context: &GlContext,
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        textures: Vec<MeshTexture>,
+    ) -> Self {
+        let count = vertices.len();
+        if let Some(index) = indices.iter().find(|&&index| index as usize >= count) {
+            panic!("Mesh index {index} is out of range for {count} vertices");
+        }
 
         // Create vertex array
-        gl::GenVertexArrays(1, &mut vao);
-        gl::BindVertexArray(vao);
+        let vao = VertexArray::new(context);
+        vao.bind();
 
         // Create vertex buffer
-        gl::GenBuffers(1, &mut vbo);
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            std::mem::size_of_val(&vertices) as isize,
-            vertices.as_ptr() as *const c_void,
-            gl::STATIC_DRAW,
-        );
+        let vbo = Buffer::new(context);
+        vbo.bind(gl::ARRAY_BUFFER);
+        unsafe {
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(vertices.as_slice()) as isize,
+                vertices.as_ptr() as *const c_void,
+                gl::STATIC_DRAW,
+            );
+        }
 
         // Create element buffer
-        gl::GenBuffers(1, &mut ebo);
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-        gl::BufferData(
-            gl::ELEMENT_ARRAY_BUFFER,
-            std::mem::size_of_val(&indices) as isize,
-            indices.as_ptr() as *const c_void,
-            gl::STATIC_DRAW,
-        );
-
-        let stride = 8 * std::mem::size_of::<f32>();
-
-        // Position attribute
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride as i32, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-
-        // Normal attribute
-        gl::VertexAttribPointer(
-            1,
-            3,
-            gl::FLOAT,
-            gl::FALSE,
-            stride as i32,
-            (3 * std::mem::size_of::<f32>()) as *const c_void,
-        );
-        gl::EnableVertexAttribArray(1);
-
-        // Texture coordinate attribute
-        gl::VertexAttribPointer(
-            2,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            stride as i32,
-            (6 * std::mem::size_of::<f32>()) as *const c_void,
-        );
-        gl::EnableVertexAttribArray(2);
-
-        // Unbind vertex array
-        gl::BindVertexArray(0);
+        let ebo = Buffer::new(context);
+        ebo.bind(gl::ELEMENT_ARRAY_BUFFER);
+        unsafe {
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                std::mem::size_of_val(indices.as_slice()) as isize,
+                indices.as_ptr() as *const c_void,
+                gl::STATIC_DRAW,
+            );
+        }
+
+        let stride = std::mem::size_of::<Vertex>() as i32;
+
+        unsafe {
+            // Position attribute
+            gl::VertexAttribPointer(
+                0,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                offset_of!(Vertex, position) as *const c_void,
+            );
+            gl::EnableVertexAttribArray(0);
+
+            // Normal attribute
+            gl::VertexAttribPointer(
+                1,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                offset_of!(Vertex, normal) as *const c_void,
+            );
+            gl::EnableVertexAttribArray(1);
+
+            // Texture coordinate attribute
+            gl::VertexAttribPointer(
+                2,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                offset_of!(Vertex, texture_coords) as *const c_void,
+            );
+            gl::EnableVertexAttribArray(2);
+
+            // Unbind vertex array
+            gl::BindVertexArray(0);
+        }
+
+        let bounds = Aabb::from_points(vertices.iter().map(|vertex| &vertex.position))
+            .unwrap_or(Aabb::new(glm::Vec3::zeros(), glm::Vec3::zeros()));
 
         Self {
             vertices,
             indices,
             textures,
+            shininess: None,
+            bounds,
             vao,
-            vbo,
-            ebo,
+            _vbo: vbo,
+            _ebo: ebo,
         }
     }
 
-    pub unsafe fn draw(&self, shader: &Shader) {
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// The box around the vertices the mesh was created with.
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    pub fn draw(&self, shader: &Shader) {
         shader.use_program();
 
-        // Bind textures
+        // Bind textures, numbering each type separately starting from 1
+        let (mut diffuse_count, mut specular_count) = (0, 0);
         for (i, texture) in self.textures.iter().enumerate() {
-            gl::ActiveTexture(gl::TEXTURE0 + i as u32);
-            gl::BindTexture(gl::TEXTURE_2D, texture.id);
-
-            let type_str = match texture.texture_type {
-                TextureType::Diffuse => "texture_diffuse",
-                TextureType::Specular => "texture_specular",
+            texture.texture.bind(i as u32);
+
+            let number = match texture.texture_type {
+                TextureType::Diffuse => {
+                    diffuse_count += 1;
+                    diffuse_count
+                }
+                TextureType::Specular => {
+                    specular_count += 1;
+                    specular_count
+                }
             };
-            gl::Uniform1f(
-                shader.get_uniform_location(&format!("material.{type_str}{i}")),
-                i as f32,
-            );
+            let type_str = texture.texture_type.uniform_prefix();
+            shader.set_uniform(&format!("material.{type_str}{number}"), i as i32);
+        }
+        unsafe { gl::ActiveTexture(gl::TEXTURE0) };
+
+        if let Some(shininess) = self.shininess {
+            shader.set_uniform("material.shininess", shininess);
         }
 
         // Draw elements
-        gl::BindVertexArray(self.vao);
-        gl::DrawElements(
-            gl::TRIANGLES,
-            self.indices.len() as i32,
-            gl::UNSIGNED_INT,
-            0 as *const c_void,
-        );
-        gl::BindVertexArray(0);
+        self.vao.bind();
+        unsafe {
+            gl::DrawElements(
+                gl::TRIANGLES,
+                self.indices.len() as i32,
+                gl::UNSIGNED_INT,
+                std::ptr::null(),
+            );
+            gl::BindVertexArray(0);
+        }
     }
 }
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use assimp::{Importer, Node, Scene};
+use assimp_sys::{
+    aiGetMaterialTexture, aiGetMaterialTextureCount, AiReturn, AiString, AiTextureType,
+};
+use nalgebra_glm as glm;
+
+use super::{Model, ModelError};
+use crate::{GlContext, Mesh, MeshTexture, Texture2D, TextureType, Vertex};
+
+impl Model {
+    /// Imports every mesh in the file at `path` through assimp. Node transforms are baked into
+    /// the vertex data, so the whole model can be drawn with a single model matrix.
+    pub fn from_assimp<P>(context: &GlContext, path: P) -> Result<Self, ModelError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let path_str = path.to_str().ok_or_else(|| ModelError::Invalid {
+            path: path.to_path_buf(),
+            message: "Path is not valid UTF-8".to_owned(),
+        })?;
+
+        let mut importer = Importer::new();
+        importer.triangulate(true);
+        importer.generate_normals(|args| args.enable = true);
+        importer.join_identical_vertices(true);
+
+        let scene = importer
+            .read_file(path_str)
+            .map_err(|message| ModelError::Assimp {
+                path: path.to_path_buf(),
+                message: message.to_owned(),
+            })?;
+        if scene.is_incomplete() {
+            return Err(ModelError::Invalid {
+                path: path.to_path_buf(),
+                message: "Incomplete scene".to_owned(),
+            });
+        }
+
+        let mut loader = AssimpLoader {
+            context,
+            path,
+            model: Model::empty(),
+            directory: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            loaded_textures: HashMap::new(),
+        };
+        loader.process_node(&scene, &scene.root_node(), glm::Mat4::identity())?;
+
+        Ok(loader.model)
+    }
+}
+
+struct AssimpLoader<'a> {
+    context: &'a GlContext,
+    path: &'a Path,
+    model: Model,
+    directory: PathBuf,
+    loaded_textures: HashMap<PathBuf, Rc<Texture2D>>,
+}
+
+impl AssimpLoader<'_> {
+    fn process_node(
+        &mut self,
+        scene: &Scene,
+        node: &Node,
+        parent_transform: glm::Mat4,
+    ) -> Result<(), ModelError> {
+        let t = node.transformation();
+        #[rustfmt::skip]
+        let local_transform = glm::mat4(
+            t.a1, t.a2, t.a3, t.a4,
+            t.b1, t.b2, t.b3, t.b4,
+            t.c1, t.c2, t.c3, t.c4,
+            t.d1, t.d2, t.d3, t.d4,
+        );
+        let transform = parent_transform * local_transform;
+
+        for &mesh_index in node.meshes() {
+            let mesh = scene
+                .mesh(mesh_index as usize)
+                .ok_or_else(|| ModelError::Invalid {
+                    path: self.path.to_path_buf(),
+                    message: format!("Node {} references missing mesh {mesh_index}", node.name()),
+                })?;
+            let mesh = self.process_mesh(scene, &mesh, &transform)?;
+            self.model.meshes.push(mesh);
+        }
+
+        for child in node.child_iter() {
+            self.process_node(scene, &child, transform)?;
+        }
+
+        Ok(())
+    }
+
+    fn process_mesh(
+        &mut self,
+        scene: &Scene,
+        mesh: &assimp::Mesh,
+        transform: &glm::Mat4,
+    ) -> Result<Mesh, ModelError> {
+        let normal_matrix = glm::inverse_transpose(glm::mat4_to_mat3(transform));
+
+        // Vertices
+        let mut vertices = Vec::with_capacity(mesh.num_vertices() as usize);
+        for i in 0..mesh.num_vertices() {
+            let position = mesh.get_vertex(i).unwrap();
+            let position = transform * glm::vec4(position.x, position.y, position.z, 1.0);
+
+            let normal = if mesh.normals.is_null() {
+                glm::Vec3::zeros()
+            } else {
+                let normal = mesh.get_normal(i).unwrap();
+                (normal_matrix * glm::vec3(normal.x, normal.y, normal.z)).normalize()
+            };
+
+            let texture_coords = if mesh.texture_coords[0].is_null() {
+                glm::Vec2::zeros()
+            } else {
+                let uv = mesh.get_texture_coord(0, i).unwrap();
+                glm::vec2(uv.x, uv.y)
+            };
+
+            vertices.push(Vertex::new(position.xyz(), normal, texture_coords));
+        }
+
+        // Indices
+        let mut indices = Vec::with_capacity(mesh.num_faces() as usize * 3);
+        for face in mesh.face_iter() {
+            for i in 0..face.num_indices as isize {
+                indices.push(face[i]);
+            }
+        }
+
+        // Textures
+        let mut textures = Vec::new();
+        if let Some(material) = scene.material_iter().nth(mesh.material_index as usize) {
+            for (ai_type, texture_type) in [
+                (AiTextureType::Diffuse, TextureType::Diffuse),
+                (AiTextureType::Specular, TextureType::Specular),
+            ] {
+                let count = unsafe { aiGetMaterialTextureCount(material.to_raw(), ai_type) };
+                for i in 0..count {
+                    let mut texture_path = AiString::default();
+                    let result = unsafe {
+                        aiGetMaterialTexture(
+                            material.to_raw(),
+                            ai_type,
+                            i,
+                            &mut texture_path,
+                            std::ptr::null(),
+                            std::ptr::null_mut(),
+                            std::ptr::null_mut(),
+                            std::ptr::null_mut(),
+                            std::ptr::null_mut(),
+                            std::ptr::null_mut(),
+                        )
+                    };
+                    if result != AiReturn::Success {
+                        continue;
+                    }
+
+                    let texture = self.load_material_texture(texture_path.as_ref())?;
+                    textures.push(MeshTexture::new(texture, texture_type));
+                }
+            }
+        }
+
+        Ok(Mesh::new(self.context, vertices, indices, textures))
+    }
+
+    fn load_material_texture(&mut self, texture_path: &str) -> Result<Rc<Texture2D>, ModelError> {
+        let full_path = self.directory.join(texture_path);
+        if let Some(texture) = self.loaded_textures.get(&full_path) {
+            return Ok(Rc::clone(texture));
+        }
+
+        let texture = Rc::new(Texture2D::from_file(self.context, &full_path)?);
+        self.loaded_textures.insert(full_path, Rc::clone(&texture));
+        Ok(texture)
+    }
+}
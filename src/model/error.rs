@@ -0,0 +1,66 @@
+use std::{fmt, path::PathBuf};
+
+use crate::{ObjError, TextureError};
+
+#[derive(Debug)]
+pub enum ModelError {
+    Obj(ObjError),
+    Texture(TextureError),
+    #[cfg(feature = "gltf")]
+    Gltf {
+        path: PathBuf,
+        error: gltf::Error,
+    },
+    /// assimp failed to read the file, with the message it gave.
+    #[cfg(feature = "assimp")]
+    Assimp {
+        path: PathBuf,
+        message: String,
+    },
+    /// A file that was read, but whose contents can't be turned into meshes.
+    Invalid {
+        path: PathBuf,
+        message: String,
+    },
+    /// None of the enabled importers handles the file.
+    Unsupported(PathBuf),
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Obj(error) => write!(f, "{error}"),
+            Self::Texture(error) => write!(f, "{error}"),
+            #[cfg(feature = "gltf")]
+            Self::Gltf { path, error } => write!(f, "{}: {error}", path.display()),
+            #[cfg(feature = "assimp")]
+            Self::Assimp { path, message } => write!(f, "{}: {message}", path.display()),
+            Self::Invalid { path, message } => write!(f, "{}: {message}", path.display()),
+            Self::Unsupported(path) => write!(f, "No importer enabled for {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Obj(error) => Some(error),
+            Self::Texture(error) => Some(error),
+            #[cfg(feature = "gltf")]
+            Self::Gltf { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ObjError> for ModelError {
+    fn from(error: ObjError) -> Self {
+        Self::Obj(error)
+    }
+}
+
+impl From<TextureError> for ModelError {
+    fn from(error: TextureError) -> Self {
+        Self::Texture(error)
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::HashMap, path::Path, rc::Rc};
+
+use ::image::{DynamicImage, ImageBuffer};
+use gltf::{buffer, image, mesh::Mode, texture, Node, Primitive};
+use nalgebra_glm as glm;
+
+use super::{Model, ModelError};
+use crate::{GlContext, Mesh, MeshTexture, Texture2D, TextureType, Vertex};
+
+impl Model {
+    /// Imports the default scene of a `.gltf` or `.glb` file without going through assimp.
+    /// Embedded and external buffers and images are both supported. Node transforms are baked
+    /// into the vertex data, same as `Model::from_assimp`.
+    pub fn from_gltf<P>(context: &GlContext, path: P) -> Result<Self, ModelError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let (document, buffers, images) = gltf::import(path).map_err(|error| ModelError::Gltf {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        let scene = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .ok_or_else(|| ModelError::Invalid {
+                path: path.to_path_buf(),
+                message: "No scenes".to_owned(),
+            })?;
+
+        let mut loader = GltfLoader {
+            context,
+            path,
+            model: Model::empty(),
+            buffers: &buffers,
+            images: &images,
+            loaded_textures: HashMap::new(),
+        };
+        for node in scene.nodes() {
+            loader.process_node(&node, glm::Mat4::identity())?;
+        }
+
+        Ok(loader.model)
+    }
+}
+
+struct GltfLoader<'a> {
+    context: &'a GlContext,
+    path: &'a Path,
+    model: Model,
+    buffers: &'a [buffer::Data],
+    images: &'a [image::Data],
+    loaded_textures: HashMap<usize, Rc<Texture2D>>,
+}
+
+impl GltfLoader<'_> {
+    fn process_node(&mut self, node: &Node, parent_transform: glm::Mat4) -> Result<(), ModelError> {
+        let transform = parent_transform * glm::Mat4::from(node.transform().matrix());
+
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                // Lines and points can't be drawn by `Mesh`, so they are left out
+                if !matches!(
+                    primitive.mode(),
+                    Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan
+                ) {
+                    eprintln!(
+                        "Warning: skipping primitive {} of mesh `{}`, {:?} isn't supported",
+                        primitive.index(),
+                        mesh.name().unwrap_or(&mesh.index().to_string()),
+                        primitive.mode(),
+                    );
+                    continue;
+                }
+
+                let mesh = self.process_primitive(&primitive, &transform)?;
+                self.model.meshes.push(mesh);
+            }
+        }
+
+        for child in node.children() {
+            self.process_node(&child, transform)?;
+        }
+
+        Ok(())
+    }
+
+    fn process_primitive(
+        &mut self,
+        primitive: &Primitive,
+        transform: &glm::Mat4,
+    ) -> Result<Mesh, ModelError> {
+        let normal_matrix = glm::inverse_transpose(glm::mat4_to_mat3(transform));
+        let reader = primitive.reader(|buffer| Some(&self.buffers[buffer.index()]));
+
+        // Vertices
+        let positions = reader.read_positions().ok_or_else(|| {
+            self.invalid(format!("Primitive {} has no positions", primitive.index()))
+        })?;
+        let mut normals = reader.read_normals();
+        let mut texture_coords = reader.read_tex_coords(0).map(|coords| coords.into_f32());
+
+        let mut vertices = Vec::with_capacity(positions.len());
+        for position in positions {
+            let position = transform * glm::vec4(position[0], position[1], position[2], 1.0);
+
+            let normal = match normals.as_mut().and_then(Iterator::next) {
+                Some(normal) => (normal_matrix * glm::Vec3::from(normal)).normalize(),
+                None => glm::Vec3::zeros(),
+            };
+
+            let uv = texture_coords
+                .as_mut()
+                .and_then(Iterator::next)
+                .map(glm::Vec2::from)
+                .unwrap_or_else(glm::Vec2::zeros);
+
+            vertices.push(Vertex::new(position.xyz(), normal, uv));
+        }
+
+        // Indices
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..vertices.len() as u32).collect(),
+        };
+        let count = vertices.len();
+        if let Some(index) = indices.iter().find(|&&index| index as usize >= count) {
+            return Err(self.invalid(format!(
+                "Primitive {} has index {index} but only {count} vertices",
+                primitive.index()
+            )));
+        }
+        let indices = triangulate(primitive.mode(), indices);
+
+        // Textures
+        let material = primitive.material();
+        let mut textures = Vec::new();
+        if let Some(info) = material.pbr_metallic_roughness().base_color_texture() {
+            let texture = self.load_texture(&info.texture())?;
+            textures.push(MeshTexture::new(texture, TextureType::Diffuse));
+        }
+        if let Some(info) = material
+            .specular()
+            .and_then(|specular| specular.specular_color_texture())
+        {
+            let texture = self.load_texture(&info.texture())?;
+            textures.push(MeshTexture::new(texture, TextureType::Specular));
+        }
+
+        Ok(Mesh::new(self.context, vertices, indices, textures))
+    }
+
+    fn load_texture(&mut self, texture: &texture::Texture) -> Result<Rc<Texture2D>, ModelError> {
+        let image_index = texture.source().index();
+        if let Some(texture) = self.loaded_textures.get(&image_index) {
+            return Ok(Rc::clone(texture));
+        }
+
+        let img = to_dynamic_image(&self.images[image_index])
+            .ok_or_else(|| self.invalid(format!("Image {image_index} has an invalid size")))?;
+
+        // glTF texture coordinates start at the top-left, which matches uploading rows unflipped
+        let texture = Texture2D::builder()
+            .flip_vertically(false)
+            .load_image(self.context, img)?;
+        let texture = Rc::new(texture);
+        self.loaded_textures
+            .insert(image_index, Rc::clone(&texture));
+        Ok(texture)
+    }
+
+    fn invalid(&self, message: String) -> ModelError {
+        ModelError::Invalid {
+            path: self.path.to_path_buf(),
+            message,
+        }
+    }
+}
+
+/// Turns the indices of a triangle strip or fan into a plain triangle list. Every other
+/// triangle of a strip is flipped so they all keep the same winding.
+fn triangulate(mode: Mode, indices: Vec<u32>) -> Vec<u32> {
+    let triangles = indices.len().saturating_sub(2);
+    match mode {
+        Mode::TriangleStrip => (0..triangles)
+            .flat_map(|i| match i % 2 {
+                0 => [indices[i], indices[i + 1], indices[i + 2]],
+                _ => [indices[i + 1], indices[i], indices[i + 2]],
+            })
+            .collect(),
+        Mode::TriangleFan => (0..triangles)
+            .flat_map(|i| [indices[0], indices[i + 1], indices[i + 2]])
+            .collect(),
+        _ => indices,
+    }
+}
+
+fn to_dynamic_image(data: &image::Data) -> Option<DynamicImage> {
+    let (width, height) = (data.width, data.height);
+    let bytes = || data.pixels.clone();
+    let shorts = || {
+        data.pixels
+            .chunks_exact(2)
+            .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]))
+            .collect::<Vec<_>>()
+    };
+    let floats = || {
+        data.pixels
+            .chunks_exact(4)
+            .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect::<Vec<_>>()
+    };
+
+    Some(match data.format {
+        image::Format::R8 => {
+            DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, bytes())?)
+        }
+        image::Format::R8G8 => {
+            DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, bytes())?)
+        }
+        image::Format::R8G8B8 => {
+            DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, bytes())?)
+        }
+        image::Format::R8G8B8A8 => {
+            DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, bytes())?)
+        }
+        image::Format::R16 => {
+            DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, shorts())?)
+        }
+        image::Format::R16G16 => {
+            DynamicImage::ImageLumaA16(ImageBuffer::from_raw(width, height, shorts())?)
+        }
+        image::Format::R16G16B16 => {
+            DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, shorts())?)
+        }
+        image::Format::R16G16B16A16 => {
+            DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, shorts())?)
+        }
+        image::Format::R32G32B32FLOAT => {
+            DynamicImage::ImageRgb32F(ImageBuffer::from_raw(width, height, floats())?)
+        }
+        image::Format::R32G32B32A32FLOAT => {
+            DynamicImage::ImageRgba32F(ImageBuffer::from_raw(width, height, floats())?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_keep_their_winding() {
+        let indices = triangulate(Mode::TriangleStrip, vec![0, 1, 2, 3, 4]);
+        assert_eq!(indices, [0, 1, 2, 2, 1, 3, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fans_share_the_first_vertex() {
+        let indices = triangulate(Mode::TriangleFan, vec![0, 1, 2, 3, 4]);
+        assert_eq!(indices, [0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn lists_and_degenerate_primitives() {
+        assert_eq!(triangulate(Mode::Triangles, vec![0, 1, 2]), [0, 1, 2]);
+        assert!(triangulate(Mode::TriangleStrip, vec![0, 1]).is_empty());
+        assert!(triangulate(Mode::TriangleFan, Vec::new()).is_empty());
+    }
+}
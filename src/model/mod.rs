@@ -0,0 +1,77 @@
+#[cfg(feature = "assimp")]
+mod assimp_importer;
+mod error;
+#[cfg(feature = "gltf")]
+mod gltf_importer;
+mod obj_importer;
+
+use std::path::Path;
+
+pub use error::ModelError;
+
+use crate::{Frustum, GlContext, Mesh, Shader};
+
+pub struct Model {
+    pub meshes: Vec<Mesh>,
+}
+
+impl Model {
+    /// Imports the model at `path`. `.gltf`/`.glb` files go through the pure-Rust glTF importer
+    /// when the `gltf` feature is enabled and everything else through assimp. Without the
+    /// `assimp` feature, `.obj` files go through the built-in OBJ parser instead, which
+    /// `from_obj` also uses directly.
+    pub fn new<P>(context: &GlContext, path: P) -> Result<Self, ModelError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        #[cfg(not(feature = "assimp"))]
+        if has_extension(path, &["obj"]) {
+            return Self::from_obj(context, path);
+        }
+        #[cfg(feature = "gltf")]
+        if has_extension(path, &["gltf", "glb"]) {
+            return Self::from_gltf(context, path);
+        }
+
+        #[cfg(feature = "assimp")]
+        {
+            Self::from_assimp(context, path)
+        }
+        #[cfg(not(feature = "assimp"))]
+        {
+            Err(ModelError::Unsupported(path.to_path_buf()))
+        }
+    }
+
+    pub fn draw(&self, shader: &Shader) {
+        for mesh in &self.meshes {
+            mesh.draw(shader);
+        }
+    }
+
+    /// Draws only the meshes whose bounds intersect `frustum`, which has to be in the model's
+    /// space, e.g. `Frustum::from_matrix(&(camera.view_projection() * model_matrix))`. Returns
+    /// how many meshes were drawn.
+    pub fn draw_visible(&self, shader: &Shader, frustum: &Frustum) -> usize {
+        let mut drawn = 0;
+        for mesh in &self.meshes {
+            if frustum.intersects_aabb(&mesh.bounds()) {
+                mesh.draw(shader);
+                drawn += 1;
+            }
+        }
+        drawn
+    }
+
+    fn empty() -> Self {
+        Self { meshes: Vec::new() }
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
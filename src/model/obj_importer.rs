@@ -0,0 +1,40 @@
+use std::{collections::HashMap, path::Path, rc::Rc};
+
+use super::{Model, ModelError};
+use crate::{GlContext, Mesh, MeshTexture, ObjData, Texture2D};
+
+impl Model {
+    /// Imports a Wavefront OBJ file and its MTL libraries with the built-in parser.
+    pub fn from_obj<P>(context: &GlContext, path: P) -> Result<Self, ModelError>
+    where
+        P: AsRef<Path>,
+    {
+        let data = ObjData::load(path)?;
+
+        let mut model = Model::empty();
+        let mut loaded_textures = HashMap::new();
+        for obj_mesh in data.meshes {
+            let material = obj_mesh.material.map(|index| &data.materials[index]);
+
+            let mut textures = Vec::new();
+            for (texture_type, texture_path) in material.into_iter().flat_map(|m| m.texture_maps())
+            {
+                let texture = match loaded_textures.get(texture_path) {
+                    Some(texture) => Rc::clone(texture),
+                    None => {
+                        let texture = Rc::new(Texture2D::from_file(context, texture_path)?);
+                        loaded_textures.insert(texture_path, Rc::clone(&texture));
+                        texture
+                    }
+                };
+                textures.push(MeshTexture::new(texture, texture_type));
+            }
+
+            let mut mesh = Mesh::new(context, obj_mesh.vertices, obj_mesh.indices, textures);
+            mesh.shininess = material.and_then(|m| m.shininess);
+            model.meshes.push(mesh);
+        }
+
+        Ok(model)
+    }
+}
@@ -0,0 +1,706 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::SplitWhitespace,
+};
+
+use nalgebra_glm as glm;
+
+use crate::{TextureType, Vertex};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parse {
+        path: Option<PathBuf>,
+        line: usize,
+        message: String,
+    },
+}
+
+impl ObjError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            path: None,
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn with_path(self, file_path: &Path) -> Self {
+        match self {
+            Self::Parse {
+                path: None,
+                line,
+                message,
+            } => Self::Parse {
+                path: Some(file_path.to_path_buf()),
+                line,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Parse {
+                path: Some(path),
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            Self::Parse {
+                path: None,
+                line,
+                message,
+            } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub diffuse_map: Option<PathBuf>,
+    pub specular_map: Option<PathBuf>,
+    pub shininess: Option<f32>,
+}
+
+impl ObjMaterial {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            diffuse_map: None,
+            specular_map: None,
+            shininess: None,
+        }
+    }
+
+    /// Parses the materials of an MTL library. Texture paths are returned as written in the file.
+    pub fn parse_library(source: &str) -> Result<Vec<Self>, ObjError> {
+        let mut materials: Vec<Self> = Vec::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = strip_comment(line);
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+
+            if keyword == "newmtl" {
+                let name = rest_of_line(line, keyword);
+                if name.is_empty() {
+                    return Err(ObjError::parse(line_number, "newmtl without a name"));
+                }
+                materials.push(Self::new(name));
+                continue;
+            }
+
+            let material = match materials.last_mut() {
+                Some(material) => material,
+                // Statements before the first `newmtl` have nothing to apply to
+                None if matches!(keyword, "Ns" | "map_Kd" | "map_Ks") => {
+                    return Err(ObjError::parse(
+                        line_number,
+                        format!("{keyword} before any newmtl"),
+                    ));
+                }
+                None => continue,
+            };
+
+            match keyword {
+                "Ns" => material.shininess = Some(parse_float(&mut tokens, line_number)?),
+                "map_Kd" => material.diffuse_map = Some(parse_map_path(line, line_number)?),
+                "map_Ks" => material.specular_map = Some(parse_map_path(line, line_number)?),
+                _ => {}
+            }
+        }
+
+        Ok(materials)
+    }
+
+    pub fn texture_maps(&self) -> impl Iterator<Item = (TextureType, &Path)> {
+        let diffuse = self
+            .diffuse_map
+            .as_deref()
+            .map(|path| (TextureType::Diffuse, path));
+        let specular = self
+            .specular_map
+            .as_deref()
+            .map(|path| (TextureType::Specular, path));
+        diffuse.into_iter().chain(specular)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjMesh {
+    pub name: String,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    /// Index into `ObjData::materials`.
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjData {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+    /// Libraries named by `mtllib` statements, as written in the file.
+    pub material_libraries: Vec<PathBuf>,
+}
+
+impl ObjData {
+    /// Reads an OBJ file along with its MTL libraries. Texture paths in the materials are made
+    /// relative to the working directory, so they can be opened directly.
+    pub fn load<P>(path: P) -> Result<Self, ObjError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let source = read_to_string(path)?;
+        let mut data = Self::parse_with_materials(&source, Vec::new(), |library| {
+            let library_path = directory.join(library);
+            let library_directory = library_path.parent().unwrap_or(Path::new(""));
+
+            let source = read_to_string(&library_path)?;
+            let mut materials =
+                ObjMaterial::parse_library(&source).map_err(|e| e.with_path(&library_path))?;
+            for material in &mut materials {
+                let maps = [&mut material.diffuse_map, &mut material.specular_map];
+                for map in maps.into_iter().flatten() {
+                    *map = library_directory.join(&*map);
+                }
+            }
+            Ok(materials)
+        })
+        .map_err(|e| e.with_path(path))?;
+
+        // Libraries are resolved at this point, so keep the paths consistent with the maps
+        for library in &mut data.material_libraries {
+            *library = directory.join(&*library);
+        }
+
+        Ok(data)
+    }
+
+    /// Parses OBJ source without touching the file system. `usemtl` statements are matched
+    /// against `materials`, which would normally come from `ObjMaterial::parse_library`.
+    pub fn parse(source: &str, materials: Vec<ObjMaterial>) -> Result<Self, ObjError> {
+        Self::parse_with_materials(source, materials, |_| Ok(Vec::new()))
+    }
+
+    fn parse_with_materials<F>(
+        source: &str,
+        materials: Vec<ObjMaterial>,
+        mut load_library: F,
+    ) -> Result<Self, ObjError>
+    where
+        F: FnMut(&Path) -> Result<Vec<ObjMaterial>, ObjError>,
+    {
+        let mut parser = ObjParser::default();
+        let mut data = Self {
+            materials,
+            ..Default::default()
+        };
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = strip_comment(line);
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+
+            match keyword {
+                "v" => {
+                    let position = parse_vec3(&mut tokens, line_number)?;
+                    parser.positions.push(position);
+                }
+                "vn" => {
+                    let normal = parse_vec3(&mut tokens, line_number)?;
+                    parser.normals.push(normal);
+                }
+                "vt" => {
+                    let u = parse_float(&mut tokens, line_number)?;
+                    let v = match tokens.next() {
+                        Some(token) => parse_float_token(token, line_number)?,
+                        None => 0.0,
+                    };
+                    parser.texture_coords.push(glm::vec2(u, v));
+                }
+                "f" => parser.add_face(tokens, line_number)?,
+                "o" | "g" => parser.start_mesh(Some(rest_of_line(line, keyword)), None),
+                "usemtl" => {
+                    let name = rest_of_line(line, keyword);
+                    if name.is_empty() {
+                        return Err(ObjError::parse(line_number, "usemtl without a name"));
+                    }
+                    parser.start_mesh(None, Some(name));
+                }
+                "mtllib" => {
+                    for library in tokens {
+                        let library = PathBuf::from(library);
+                        data.materials.extend(load_library(&library)?);
+                        data.material_libraries.push(library);
+                    }
+                }
+                // Smoothing groups, lines, points and free-form geometry aren't used
+                _ => {}
+            }
+        }
+
+        // Materials are matched by name last, since `mtllib` may come after `usemtl`
+        for (mut mesh, material_name) in parser.finish() {
+            mesh.material =
+                material_name.and_then(|name| data.materials.iter().position(|m| m.name == name));
+            data.meshes.push(mesh);
+        }
+
+        Ok(data)
+    }
+}
+
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+#[derive(Default)]
+struct MeshBuilder {
+    name: String,
+    material_name: Option<String>,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    vertex_indices: HashMap<VertexKey, u32>,
+    /// Vertices without a `vn`, which get a smooth normal from their faces instead.
+    generated_normals: Vec<bool>,
+}
+
+impl MeshBuilder {
+    fn build(mut self) -> (ObjMesh, Option<String>) {
+        for (vertex, generated) in self.vertices.iter_mut().zip(self.generated_normals) {
+            if generated && vertex.normal != glm::Vec3::zeros() {
+                vertex.normal = vertex.normal.normalize();
+            }
+        }
+
+        let mesh = ObjMesh {
+            name: self.name,
+            vertices: self.vertices,
+            indices: self.indices,
+            material: None,
+        };
+        (mesh, self.material_name)
+    }
+}
+
+#[derive(Default)]
+struct ObjParser {
+    positions: Vec<glm::Vec3>,
+    normals: Vec<glm::Vec3>,
+    texture_coords: Vec<glm::Vec2>,
+    meshes: Vec<(ObjMesh, Option<String>)>,
+    current: MeshBuilder,
+}
+
+impl ObjParser {
+    /// Starts a new mesh for a new group or material. Whatever isn't given carries over from
+    /// the current mesh, since OBJ materials stay active across groups.
+    fn start_mesh(&mut self, name: Option<&str>, material_name: Option<&str>) {
+        let name = name.map_or_else(|| self.current.name.clone(), str::to_owned);
+        let material_name = material_name
+            .map(str::to_owned)
+            .or_else(|| self.current.material_name.clone());
+
+        if self.current.indices.is_empty() {
+            self.current.name = name;
+            self.current.material_name = material_name;
+        } else {
+            let builder = std::mem::replace(
+                &mut self.current,
+                MeshBuilder {
+                    name,
+                    material_name,
+                    ..Default::default()
+                },
+            );
+            self.meshes.push(builder.build());
+        }
+    }
+
+    fn add_face(&mut self, tokens: SplitWhitespace, line_number: usize) -> Result<(), ObjError> {
+        let mut face = Vec::new();
+        for token in tokens {
+            let key = self.parse_vertex_key(token, line_number)?;
+            face.push(self.vertex_index(key));
+        }
+
+        if face.len() < 3 {
+            return Err(ObjError::parse(
+                line_number,
+                format!("face has {} vertices, expected at least 3", face.len()),
+            ));
+        }
+
+        // Fan triangulation, which assumes n-gons are convex like most exporters produce
+        for i in 1..face.len() - 1 {
+            let triangle = [face[0], face[i], face[i + 1]];
+            self.current.indices.extend_from_slice(&triangle);
+
+            let [a, b, c] = triangle.map(|index| self.current.vertices[index as usize].position);
+            let face_normal = (b - a).cross(&(c - a));
+            for index in triangle {
+                if self.current.generated_normals[index as usize] {
+                    self.current.vertices[index as usize].normal += face_normal;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_vertex_key(&self, token: &str, line_number: usize) -> Result<VertexKey, ObjError> {
+        let mut parts = token.split('/');
+        let position = parts.next().unwrap_or_default();
+        let texture_coords = parts.next().filter(|part| !part.is_empty());
+        let normal = parts.next().filter(|part| !part.is_empty());
+        if parts.next().is_some() {
+            return Err(ObjError::parse(
+                line_number,
+                format!("invalid face vertex `{token}`"),
+            ));
+        }
+
+        let position = resolve_index(position, self.positions.len(), "v", line_number)?;
+        let texture_coords = texture_coords
+            .map(|index| resolve_index(index, self.texture_coords.len(), "vt", line_number))
+            .transpose()?;
+        let normal = normal
+            .map(|index| resolve_index(index, self.normals.len(), "vn", line_number))
+            .transpose()?;
+
+        Ok((position, texture_coords, normal))
+    }
+
+    fn vertex_index(&mut self, key: VertexKey) -> u32 {
+        if let Some(&index) = self.current.vertex_indices.get(&key) {
+            return index;
+        }
+
+        let (position, texture_coords, normal) = key;
+        let vertex = Vertex::new(
+            self.positions[position],
+            normal.map_or_else(glm::Vec3::zeros, |i| self.normals[i]),
+            texture_coords.map_or_else(glm::Vec2::zeros, |i| self.texture_coords[i]),
+        );
+
+        let index = self.current.vertices.len() as u32;
+        self.current.vertices.push(vertex);
+        self.current.generated_normals.push(normal.is_none());
+        self.current.vertex_indices.insert(key, index);
+        index
+    }
+
+    fn finish(mut self) -> Vec<(ObjMesh, Option<String>)> {
+        if !self.current.indices.is_empty() {
+            self.meshes.push(self.current.build());
+        }
+        self.meshes
+    }
+}
+
+/// Turns a 1-based (or negative, relative to the end) OBJ index into a 0-based one.
+fn resolve_index(
+    token: &str,
+    count: usize,
+    kind: &str,
+    line_number: usize,
+) -> Result<usize, ObjError> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| ObjError::parse(line_number, format!("invalid {kind} index `{token}`")))?;
+
+    let resolved = match index {
+        1.. => index - 1,
+        ..=-1 => count as i64 + index,
+        0 => -1,
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::parse(
+            line_number,
+            format!("{kind} index {index} out of range, {count} defined so far"),
+        ));
+    }
+
+    Ok(resolved as usize)
+}
+
+fn read_to_string(path: &Path) -> Result<String, ObjError> {
+    std::fs::read_to_string(path).map_err(|error| ObjError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#').map_or(line, |(before, _)| before)
+}
+
+/// Everything after the keyword, for statements whose argument may contain spaces.
+fn rest_of_line<'a>(line: &'a str, keyword: &str) -> &'a str {
+    line.trim_start()[keyword.len()..].trim()
+}
+
+fn parse_float_token(token: &str, line_number: usize) -> Result<f32, ObjError> {
+    token
+        .parse()
+        .map_err(|_| ObjError::parse(line_number, format!("invalid number `{token}`")))
+}
+
+fn parse_float(tokens: &mut SplitWhitespace, line_number: usize) -> Result<f32, ObjError> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| ObjError::parse(line_number, "missing number"))?;
+    parse_float_token(token, line_number)
+}
+
+fn parse_vec3(tokens: &mut SplitWhitespace, line_number: usize) -> Result<glm::Vec3, ObjError> {
+    Ok(glm::vec3(
+        parse_float(tokens, line_number)?,
+        parse_float(tokens, line_number)?,
+        parse_float(tokens, line_number)?,
+    ))
+}
+
+/// Texture maps may be preceded by options such as `-s 1 1 1`, so the path is the last token.
+fn parse_map_path(line: &str, line_number: usize) -> Result<PathBuf, ObjError> {
+    line.split_whitespace()
+        .skip(1)
+        .last()
+        .map(PathBuf::from)
+        .ok_or_else(|| ObjError::parse(line_number, "texture map without a file name"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn parse(source: &str) -> ObjData {
+        ObjData::parse(source, Vec::new()).unwrap()
+    }
+
+    fn parse_error_line(source: &str) -> usize {
+        match ObjData::parse(source, Vec::new()) {
+            Err(ObjError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    fn assert_vec3_eq(actual: glm::Vec3, expected: glm::Vec3) {
+        assert!(
+            (actual - expected).norm() < EPSILON,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn shared_vertices_are_deduplicated() {
+        let data = parse(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+             vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+             vn 0 0 1\n\
+             f 1/1/1 2/2/1 3/3/1\n\
+             f 1/1/1 3/3/1 4/4/1\n\
+             f 1/2/1 3/3/1 4/4/1\n",
+        );
+        let mesh = &data.meshes[0];
+
+        // The last face uses position 1 with other texture coords, which is a new vertex
+        assert_eq!(mesh.vertices.len(), 5);
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3, 4, 2, 3]);
+        assert_eq!(mesh.vertices[4].position, glm::vec3(0.0, 0.0, 0.0));
+        assert_eq!(mesh.vertices[4].texture_coords, glm::vec2(1.0, 0.0));
+    }
+
+    #[test]
+    fn quads_and_ngons_are_fan_triangulated() {
+        let data = parse(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv -1 1 0\n\
+             f 1 2 3 4\n\
+             o pentagon\n\
+             f 1 2 3 4 5\n",
+        );
+
+        assert_eq!(data.meshes.len(), 2);
+        assert_eq!(data.meshes[0].indices, [0, 1, 2, 0, 2, 3]);
+        assert_eq!(data.meshes[1].name, "pentagon");
+        assert_eq!(data.meshes[1].indices, [0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_latest() {
+        let data = parse(
+            "v 5 5 5\n\
+             v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5 0.5\nvn 0 0 1\n\
+             f -3/-1/-1 -2/-1/-1 -1/-1/-1\n",
+        );
+        let mesh = &data.meshes[0];
+
+        let positions: Vec<_> = mesh.vertices.iter().map(|v| v.position).collect();
+        assert_eq!(
+            positions,
+            [
+                glm::vec3(0.0, 0.0, 0.0),
+                glm::vec3(1.0, 0.0, 0.0),
+                glm::vec3(0.0, 1.0, 0.0),
+            ]
+        );
+        assert!(mesh
+            .vertices
+            .iter()
+            .all(|v| v.texture_coords == glm::vec2(0.5, 0.5)));
+    }
+
+    #[test]
+    fn out_of_range_indices_report_their_line() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\n# comment\nf 1 2 4\n";
+        assert_eq!(parse_error_line(source), 5);
+
+        assert_eq!(parse_error_line("v 0 0 0\nf 1 1 -2\n"), 2);
+        assert_eq!(parse_error_line("v 0 0 0\nf 0 1 1\n"), 2);
+        assert_eq!(parse_error_line("v 0 0 0\nvt 0 0\n\nf 1/2 1/1 1/1\n"), 4);
+        assert_eq!(parse_error_line("v 0 0 0\nf 1//1 1 1\n"), 2);
+
+        // The error message names the kind of index
+        let error = ObjData::parse("v 0 0 0\nf 1 1 2\n", Vec::new()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: v index 2 out of range, 1 defined so far"
+        );
+    }
+
+    #[test]
+    fn missing_normals_are_generated_from_faces() {
+        // Two triangles folded along the y axis, one facing +z and one facing +x
+        let data = parse(
+            "v 0 0 0\nv 0 1 0\nv -1 0 0\nv 0 0 -1\n\
+             f 1 2 3\n\
+             f 1 4 2\n",
+        );
+        let mesh = &data.meshes[0];
+        let diagonal = glm::vec3(1.0, 0.0, 1.0).normalize();
+
+        assert_vec3_eq(mesh.vertices[0].normal, diagonal);
+        assert_vec3_eq(mesh.vertices[1].normal, diagonal);
+        assert_vec3_eq(mesh.vertices[2].normal, glm::vec3(0.0, 0.0, 1.0));
+        assert_vec3_eq(mesh.vertices[3].normal, glm::vec3(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn given_normals_are_kept() {
+        let data = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 2\nf 1//1 2//1 3//1\n");
+        assert!(data.meshes[0]
+            .vertices
+            .iter()
+            .all(|v| v.normal == glm::vec3(0.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn usemtl_before_mtllib() {
+        let mut libraries = Vec::new();
+        let data = ObjData::parse_with_materials(
+            "usemtl wood\n\
+             v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             f 1 2 3\n\
+             usemtl missing\n\
+             f 3 2 1\n\
+             mtllib materials.mtl\n",
+            Vec::new(),
+            |library| {
+                libraries.push(library.to_path_buf());
+                ObjMaterial::parse_library("newmtl metal\nnewmtl wood\n")
+            },
+        )
+        .unwrap();
+
+        assert_eq!(libraries, [PathBuf::from("materials.mtl")]);
+        assert_eq!(data.material_libraries, libraries);
+        assert_eq!(data.meshes.len(), 2);
+        assert_eq!(data.meshes[0].material, Some(1));
+        assert_eq!(data.meshes[1].material, None);
+    }
+
+    #[test]
+    fn materials_carry_over_to_new_groups() {
+        let materials = vec![ObjMaterial::new("wood")];
+        let data = ObjData::parse(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             usemtl wood\n\
+             g first\nf 1 2 3\n\
+             g second\nf 1 2 3\n",
+            materials,
+        )
+        .unwrap();
+
+        let names: Vec<_> = data.meshes.iter().map(|mesh| mesh.name.as_str()).collect();
+        assert_eq!(names, ["first", "second"]);
+        assert!(data.meshes.iter().all(|mesh| mesh.material == Some(0)));
+    }
+
+    #[test]
+    fn material_library() {
+        let materials = ObjMaterial::parse_library(
+            "# exported\n\
+             newmtl plain\n\
+             Kd 1 1 1\n\
+             \n\
+             newmtl crate box\n\
+             Ns 32.5\n\
+             map_Kd textures/crate.png\n\
+             map_Ks -s 1 1 1 textures/crate_specular.png\n",
+        )
+        .unwrap();
+
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0], ObjMaterial::new("plain"));
+
+        let crate_box = &materials[1];
+        assert_eq!(crate_box.name, "crate box");
+        assert_eq!(crate_box.shininess, Some(32.5));
+        assert_eq!(
+            crate_box.diffuse_map.as_deref(),
+            Some(Path::new("textures/crate.png"))
+        );
+        assert_eq!(
+            crate_box.specular_map.as_deref(),
+            Some(Path::new("textures/crate_specular.png"))
+        );
+
+        let maps: Vec<_> = crate_box.texture_maps().map(|(kind, _)| kind).collect();
+        assert_eq!(maps, [TextureType::Diffuse, TextureType::Specular]);
+    }
+
+    #[test]
+    fn material_library_errors() {
+        let line = |source| match ObjMaterial::parse_library(source) {
+            Err(ObjError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+
+        assert_eq!(line("Ns 10\n"), 1);
+        assert_eq!(line("newmtl a\nNs shiny\n"), 2);
+        assert_eq!(line("newmtl a\n\nmap_Kd\n"), 3);
+        assert_eq!(line("newmtl\n"), 1);
+    }
+}
@@ -0,0 +1,135 @@
+//! Owned OpenGL objects that are deleted when dropped.
+//!
+//! GL objects belong to the context of the thread that created them, so none of these types
+//! are `Send` or `Sync`. The module docs of `context.rs` explain the safety contract.
+
+use std::marker::PhantomData;
+
+use gl::types::{GLenum, GLuint};
+
+use crate::GlContext;
+
+/// Declares an owned GL object type with an `id` accessor and a `Drop` that calls `$delete`.
+macro_rules! gl_object {
+    ($(#[$meta:meta])* $name:ident, |$id:ident| $delete:expr) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct $name {
+            id: GLuint,
+            _not_send: PhantomData<*const ()>,
+        }
+
+        impl $name {
+            /// Takes ownership of an existing object, which gets deleted with the wrapper.
+            ///
+            /// # Safety
+            ///
+            /// `id` has to name an object of this type in a context that is current under the
+            /// same terms as a `GlContext`, and nothing else may delete it.
+            pub unsafe fn from_id(id: GLuint) -> Self {
+                Self {
+                    id,
+                    _not_send: PhantomData,
+                }
+            }
+
+            pub fn id(&self) -> GLuint {
+                self.id
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                let $id = self.id;
+                unsafe { $delete }
+            }
+        }
+    };
+}
+
+gl_object!(VertexArray, |id| gl::DeleteVertexArrays(1, &id));
+gl_object!(Buffer, |id| gl::DeleteBuffers(1, &id));
+gl_object!(Texture, |id| gl::DeleteTextures(1, &id));
+gl_object!(Program, |id| gl::DeleteProgram(id));
+gl_object!(Framebuffer, |id| gl::DeleteFramebuffers(1, &id));
+gl_object!(Renderbuffer, |id| gl::DeleteRenderbuffers(1, &id));
+
+impl VertexArray {
+    pub fn new(_context: &GlContext) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut id);
+            Self::from_id(id)
+        }
+    }
+
+    pub fn bind(&self) {
+        unsafe { gl::BindVertexArray(self.id) };
+    }
+}
+
+impl Buffer {
+    pub fn new(_context: &GlContext) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            Self::from_id(id)
+        }
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        unsafe { gl::BindBuffer(target, self.id) };
+    }
+}
+
+impl Texture {
+    pub fn new(_context: &GlContext) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenTextures(1, &mut id);
+            Self::from_id(id)
+        }
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        unsafe { gl::BindTexture(target, self.id) };
+    }
+}
+
+impl Program {
+    pub fn new(_context: &GlContext) -> Self {
+        unsafe { Self::from_id(gl::CreateProgram()) }
+    }
+
+    pub fn use_program(&self) {
+        unsafe { gl::UseProgram(self.id) };
+    }
+}
+
+impl Framebuffer {
+    pub fn new(_context: &GlContext) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut id);
+            Self::from_id(id)
+        }
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        unsafe { gl::BindFramebuffer(target, self.id) };
+    }
+}
+
+impl Renderbuffer {
+    pub fn new(_context: &GlContext) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenRenderbuffers(1, &mut id);
+            Self::from_id(id)
+        }
+    }
+
+    pub fn bind(&self) {
+        unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, self.id) };
+    }
+}
@@ -0,0 +1,113 @@
+use std::ffi::c_void;
+
+use image::RgbaImage;
+
+use crate::{
+    objects::{Framebuffer, Renderbuffer},
+    GlContext,
+};
+
+/// A framebuffer with an RGBA8 color and a depth/stencil attachment, for rendering without
+/// touching the window.
+pub struct OffscreenTarget {
+    framebuffer: Framebuffer,
+    _color: Renderbuffer,
+    _depth_stencil: Renderbuffer,
+    width: u32,
+    height: u32,
+}
+
+impl OffscreenTarget {
+    pub fn new(context: &GlContext, width: u32, height: u32) -> Result<Self, String> {
+        let framebuffer = Framebuffer::new(context);
+        framebuffer.bind(gl::FRAMEBUFFER);
+
+        let color = Renderbuffer::new(context);
+        let depth_stencil = Renderbuffer::new(context);
+        let status = unsafe {
+            color.bind();
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width as i32, height as i32);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                color.id(),
+            );
+
+            depth_stencil.bind();
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH24_STENCIL8,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth_stencil.id(),
+            );
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            status
+        };
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!(
+                "Offscreen framebuffer of {width}x{height} is incomplete (status {status:#x})"
+            ));
+        }
+
+        Ok(Self {
+            framebuffer,
+            _color: color,
+            _depth_stencil: depth_stencil,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Makes the target the draw and read framebuffer and sets the viewport to cover it.
+    pub fn bind(&self) {
+        self.framebuffer.bind(gl::FRAMEBUFFER);
+        unsafe { gl::Viewport(0, 0, self.width as i32, self.height as i32) };
+    }
+
+    /// Reads back what was rendered into the target.
+    pub fn read_pixels(&self) -> RgbaImage {
+        self.framebuffer.bind(gl::READ_FRAMEBUFFER);
+        read_pixels(self.width, self.height)
+    }
+}
+
+/// Reads the bottom-left `width * height` pixels of the bound read framebuffer, flipped so the
+/// first row is the top one like image files expect.
+pub(crate) fn read_pixels(width: u32, height: u32) -> RgbaImage {
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    unsafe {
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            width as i32,
+            height as i32,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut c_void,
+        );
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 4);
+    }
+
+    let image = RgbaImage::from_raw(width, height, pixels).unwrap();
+    image::imageops::flip_vertical(&image)
+}
@@ -0,0 +1,149 @@
+use std::{
+    ffi::{c_void, CStr},
+    path::{Path, PathBuf},
+};
+
+use gl::types::{GLenum, GLuint};
+
+use super::{preprocess::PreprocessedSource, ShaderStage};
+
+/// On-disk cache of linked program binaries, one file per combination of sources and driver.
+/// Needs OpenGL 4.1 or `ARB_get_program_binary`, and does nothing without it.
+pub(super) struct ProgramBinaryCache<'a> {
+    dir: &'a Path,
+    key: u64,
+}
+
+impl<'a> ProgramBinaryCache<'a> {
+    /// Returns `None` if the driver can't save program binaries.
+    pub unsafe fn new(
+        dir: &'a Path,
+        sources: &[(ShaderStage, PreprocessedSource)],
+    ) -> Option<Self> {
+        if !gl::GetProgramBinary::is_loaded() || !gl::ProgramBinary::is_loaded() {
+            return None;
+        }
+        let mut format_count = 0;
+        gl::GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, &mut format_count);
+        if format_count == 0 {
+            return None;
+        }
+
+        // Binaries only load on the driver that made them, so it's part of the key
+        let mut hasher = Fnv1a::default();
+        for name in [gl::VENDOR, gl::RENDERER, gl::VERSION] {
+            hasher.write(gl_string(name).as_bytes());
+        }
+        for (stage, source) in sources {
+            hasher.write(stage.name().as_bytes());
+            hasher.write(source.text.as_bytes());
+        }
+
+        Some(Self {
+            dir,
+            key: hasher.finish(),
+        })
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(format!("{:016x}.bin", self.key))
+    }
+
+    /// Creates a program from the cached binary, if there is one and the driver accepts it.
+    /// Rejected binaries are removed so they get replaced.
+    pub unsafe fn load(&self) -> Option<GLuint> {
+        let path = self.path();
+        let data = std::fs::read(&path).ok()?;
+        if data.len() < 4 {
+            return None;
+        }
+        let (format, binary) = data.split_at(4);
+        let format = GLenum::from_le_bytes(format.try_into().unwrap());
+
+        let program = gl::CreateProgram();
+        gl::ProgramBinary(
+            program,
+            format,
+            binary.as_ptr() as *const c_void,
+            binary.len() as i32,
+        );
+
+        let mut link_success = 0;
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut link_success);
+        if link_success == 0 {
+            gl::DeleteProgram(program);
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(program)
+    }
+
+    /// Saves the binary of a program linked with `PROGRAM_BINARY_RETRIEVABLE_HINT` set. Failing
+    /// to save only costs the next startup a compile, so errors are printed as warnings.
+    pub unsafe fn store(&self, program: GLuint) {
+        let mut length = 0;
+        gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut length);
+        if length <= 0 {
+            return;
+        }
+
+        let mut binary = vec![0u8; length as usize];
+        let (mut written, mut format) = (0, 0);
+        gl::GetProgramBinary(
+            program,
+            length,
+            &mut written,
+            &mut format,
+            binary.as_mut_ptr() as *mut c_void,
+        );
+        binary.truncate(written as usize);
+
+        let mut data = format.to_le_bytes().to_vec();
+        data.extend_from_slice(&binary);
+
+        let path = self.path();
+        if let Err(e) = std::fs::create_dir_all(self.dir).and_then(|_| std::fs::write(&path, data))
+        {
+            eprintln!(
+                "Warning: failed to write program binary {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+unsafe fn gl_string(name: GLenum) -> String {
+    let string = gl::GetString(name);
+    if string.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(string as *const _)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 64-bit FNV-1a, used over `DefaultHasher` because cache file names have to stay the same
+/// across Rust versions.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+        // Separate consecutive writes so ("ab", "c") and ("a", "bc") differ
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(0x100000001b3);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
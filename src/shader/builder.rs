@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use gl::types::GLenum;
+
+use super::{Shader, ShaderError};
+use crate::GlContext;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderStage {
+    Vertex,
+    TessControl,
+    TessEvaluation,
+    Geometry,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    pub(super) fn to_gl(self) -> GLenum {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::TessControl => gl::TESS_CONTROL_SHADER,
+            ShaderStage::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Compute => gl::COMPUTE_SHADER,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ShaderStage::Vertex => "vertex",
+            ShaderStage::TessControl => "tessellation control",
+            ShaderStage::TessEvaluation => "tessellation evaluation",
+            ShaderStage::Geometry => "geometry",
+            ShaderStage::Fragment => "fragment",
+            ShaderStage::Compute => "compute",
+        }
+    }
+}
+
+/// Collects the source files of a graphics program, one per stage. Setting a stage again
+/// replaces its file.
+#[derive(Clone, Debug, Default)]
+pub struct ShaderBuilder {
+    stages: Vec<(ShaderStage, PathBuf)>,
+    defines: Vec<(String, String)>,
+    binary_cache: Option<PathBuf>,
+}
+
+impl ShaderBuilder {
+    pub fn vertex<P: AsRef<Path>>(self, path: P) -> Self {
+        self.stage(ShaderStage::Vertex, path)
+    }
+
+    /// Tessellation stages need OpenGL 4.0.
+    pub fn tess_control<P: AsRef<Path>>(self, path: P) -> Self {
+        self.stage(ShaderStage::TessControl, path)
+    }
+
+    pub fn tess_evaluation<P: AsRef<Path>>(self, path: P) -> Self {
+        self.stage(ShaderStage::TessEvaluation, path)
+    }
+
+    pub fn geometry<P: AsRef<Path>>(self, path: P) -> Self {
+        self.stage(ShaderStage::Geometry, path)
+    }
+
+    pub fn fragment<P: AsRef<Path>>(self, path: P) -> Self {
+        self.stage(ShaderStage::Fragment, path)
+    }
+
+    /// Adds a `#define name value` line after the `#version` of every stage.
+    pub fn define(mut self, name: &str, value: impl ToString) -> Self {
+        self.defines.push((name.to_owned(), value.to_string()));
+        self
+    }
+
+    /// Caches the linked program's binary in `dir`, so later runs with the same sources and
+    /// driver skip compiling. Falls back to compiling when the driver rejects a binary or can't
+    /// save them at all.
+    pub fn binary_cache<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.binary_cache = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Compiles every stage and links them. Compute shaders go through `ComputeShader`
+    /// instead, since they can't be linked with other stages.
+    pub fn build(&self, context: &GlContext) -> Result<Shader, ShaderError> {
+        Shader::from_stages(
+            context,
+            self.stages.clone(),
+            self.defines.clone(),
+            self.binary_cache.clone(),
+        )
+    }
+
+    fn stage<P: AsRef<Path>>(mut self, stage: ShaderStage, path: P) -> Self {
+        self.stages.retain(|&(existing, _)| existing != stage);
+        self.stages.push((stage, path.as_ref().to_path_buf()));
+        self
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+use gl::types::GLbitfield;
+
+use super::{Shader, ShaderError, ShaderStage};
+use crate::GlContext;
+
+/// A program made of a single compute shader, which needs OpenGL 4.3. Uniforms, reflection
+/// and reloading work through the wrapped `Shader`.
+pub struct ComputeShader {
+    shader: Shader,
+}
+
+impl ComputeShader {
+    pub fn new<P: AsRef<Path>>(context: &GlContext, path: P) -> Result<Self, ShaderError> {
+        Self::with_defines(context, path, &[])
+    }
+
+    pub fn with_defines<P: AsRef<Path>>(
+        context: &GlContext,
+        path: P,
+        defines: &[(&str, &str)],
+    ) -> Result<Self, ShaderError> {
+        let defines = defines
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect();
+        let shader = Shader::from_stages(
+            context,
+            vec![(ShaderStage::Compute, path.as_ref().to_path_buf())],
+            defines,
+            None,
+        )?;
+
+        Ok(Self { shader })
+    }
+
+    /// The `local_size_x/y/z` the shader declared.
+    pub fn work_group_size(&self) -> [u32; 3] {
+        let mut size = [0; 3];
+        unsafe {
+            gl::GetProgramiv(
+                self.shader.program.id(),
+                gl::COMPUTE_WORK_GROUP_SIZE,
+                size.as_mut_ptr(),
+            );
+        }
+        size.map(|n| n as u32)
+    }
+
+    /// Uses the program and runs `x * y * z` work groups.
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        self.shader.use_program();
+        unsafe { gl::DispatchCompute(x, y, z) };
+    }
+
+    /// Runs enough work groups to cover `width * height * depth` invocations, rounding up
+    /// when the size isn't a multiple of the work group size.
+    pub fn dispatch_covering(&self, width: u32, height: u32, depth: u32) {
+        let [x, y, z] = self.work_group_size();
+        self.dispatch(
+            width.div_ceil(x.max(1)),
+            height.div_ceil(y.max(1)),
+            depth.div_ceil(z.max(1)),
+        );
+    }
+
+    /// Makes the shader's writes visible to the operations in `barriers`, e.g.
+    /// `gl::SHADER_STORAGE_BARRIER_BIT` before reading a storage buffer in a later draw.
+    pub fn memory_barrier(_context: &GlContext, barriers: GLbitfield) {
+        unsafe { gl::MemoryBarrier(barriers) };
+    }
+
+    /// Waits for all shader writes before any later operation, for when the consumer of the
+    /// results isn't known.
+    pub fn memory_barrier_all(_context: &GlContext) {
+        unsafe { gl::MemoryBarrier(gl::ALL_BARRIER_BITS) };
+    }
+}
+
+impl Deref for ComputeShader {
+    type Target = Shader;
+
+    fn deref(&self) -> &Shader {
+        &self.shader
+    }
+}
+
+impl DerefMut for ComputeShader {
+    fn deref_mut(&mut self) -> &mut Shader {
+        &mut self.shader
+    }
+}
@@ -0,0 +1,167 @@
+use std::{fmt, path::PathBuf};
+
+use super::ShaderStage;
+
+#[derive(Debug)]
+pub enum ShaderError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// A source file that can't be handed to OpenGL, like one that isn't UTF-8, contains a NUL
+    /// byte or has a broken `#include`.
+    InvalidSource {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+    /// Every stage that failed to compile.
+    Compile(Vec<CompileFailure>),
+    Link {
+        log: String,
+        diagnostics: Vec<ShaderDiagnostic>,
+    },
+    /// Uniforms that aren't active in the program, which includes any the compiler optimized
+    /// away.
+    MissingUniforms(Vec<String>),
+    MissingUniformBlock(String),
+    MissingStorageBlock(String),
+    /// A uniform block that's larger than the buffer meant to back it.
+    UniformBlockTooLarge {
+        name: String,
+        block_size: usize,
+        buffer_size: usize,
+    },
+}
+
+impl ShaderError {
+    /// The diagnostics of all failed stages, or of the linker.
+    pub fn diagnostics(&self) -> Vec<&ShaderDiagnostic> {
+        match self {
+            Self::Compile(failures) => failures
+                .iter()
+                .flat_map(|failure| &failure.diagnostics)
+                .collect(),
+            Self::Link { diagnostics, .. } => diagnostics.iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::InvalidSource {
+                path,
+                line: Some(line),
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            Self::InvalidSource {
+                path,
+                line: None,
+                message,
+            } => write!(f, "{}: {message}", path.display()),
+            Self::Compile(failures) => {
+                for (i, failure) in failures.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{failure}")?;
+                }
+                Ok(())
+            }
+            Self::Link { diagnostics, .. } => {
+                write!(f, "Failed to link shader program")?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n  {diagnostic}")?;
+                }
+                Ok(())
+            }
+            Self::MissingUniforms(names) => match names.as_slice() {
+                [name] => write!(f, "Uniform `{name}` not found in shader program"),
+                _ => {
+                    let names: Vec<String> = names.iter().map(|name| format!("`{name}`")).collect();
+                    write!(
+                        f,
+                        "Uniforms not found in shader program: {}",
+                        names.join(", ")
+                    )
+                }
+            },
+            Self::MissingUniformBlock(name) => {
+                write!(f, "Uniform block `{name}` not found in shader program")
+            }
+            Self::MissingStorageBlock(name) => {
+                write!(
+                    f,
+                    "Shader storage block `{name}` not found in shader program"
+                )
+            }
+            Self::UniformBlockTooLarge {
+                name,
+                block_size,
+                buffer_size,
+            } => write!(
+                f,
+                "Uniform block `{name}` is {block_size} bytes, but the buffer only holds \
+                 {buffer_size}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The compiler output of one stage that failed to compile.
+#[derive(Clone, Debug)]
+pub struct CompileFailure {
+    pub stage: ShaderStage,
+    pub path: PathBuf,
+    /// The full info log, with line numbers of the preprocessed source.
+    pub log: String,
+    /// The log split into messages, with line numbers mapped back to the original files.
+    pub diagnostics: Vec<ShaderDiagnostic>,
+}
+
+impl fmt::Display for CompileFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to compile {} shader {}",
+            self.stage.name(),
+            self.path.display()
+        )?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n  {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+/// One message of a compiler or linker log. Messages in a format that isn't recognized keep
+/// their whole log line and have no location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderDiagnostic {
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: {}", file.display(), self.message),
+            (Some(file), None) => write!(f, "{}: {}", file.display(), self.message),
+            (None, Some(line)) => write!(f, "{line}: {}", self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
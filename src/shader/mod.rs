@@ -0,0 +1,503 @@
+extern crate gl;
+
+mod binary_cache;
+mod builder;
+mod compute;
+mod error;
+mod preprocess;
+mod reflection;
+
+use gl::types::{GLchar, GLint, GLuint};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::CString,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{objects::Program, GlContext, Uniform};
+use binary_cache::ProgramBinaryCache;
+use preprocess::PreprocessedSource;
+
+pub use builder::{ShaderBuilder, ShaderStage};
+pub use compute::ComputeShader;
+pub use error::{CompileFailure, ShaderDiagnostic, ShaderError};
+pub use reflection::{ActiveAttribute, ActiveUniform};
+
+/// What `Shader::set_uniform` does with names that have no location in the program, which
+/// includes uniforms the GLSL compiler optimized away.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingUniformPolicy {
+    /// Print a warning the first time each missing name is set.
+    #[default]
+    WarnOnce,
+    /// Return an error from `try_set_uniform`, which makes `set_uniform` panic.
+    Error,
+    Ignore,
+}
+
+pub struct Shader {
+    program: Program,
+    stages: Vec<(ShaderStage, PathBuf)>,
+    defines: Vec<(String, String)>,
+    binary_cache: Option<PathBuf>,
+    /// Source files with their modification times as of the last (re)load.
+    watched_files: Vec<(PathBuf, Option<SystemTime>)>,
+    uniforms: Vec<ActiveUniform>,
+    attributes: Vec<ActiveAttribute>,
+    uniform_locations: RefCell<HashMap<String, GLint>>,
+    /// Kept so reloading can bind the new program's blocks the same way.
+    uniform_block_bindings: RefCell<HashMap<String, u32>>,
+    storage_block_bindings: RefCell<HashMap<String, u32>>,
+    missing_uniform_policy: MissingUniformPolicy,
+}
+
+impl Shader {
+    pub fn new(
+        context: &GlContext,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+    ) -> Result<Self, ShaderError> {
+        Self::with_defines(context, vertex_shader_path, fragment_shader_path, &[])
+    }
+
+    /// Builds the program with `#define NAME VALUE` lines inserted after each shader's
+    /// `#version`, e.g. to size arrays from constants on the Rust side.
+    pub fn with_defines(
+        context: &GlContext,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Self, ShaderError> {
+        let mut builder = Self::builder()
+            .vertex(vertex_shader_path)
+            .fragment(fragment_shader_path);
+        for &(name, value) in defines {
+            builder = builder.define(name, value);
+        }
+        builder.build(context)
+    }
+
+    pub fn builder() -> ShaderBuilder {
+        ShaderBuilder::default()
+    }
+
+    fn from_stages(
+        _context: &GlContext,
+        stages: Vec<(ShaderStage, PathBuf)>,
+        defines: Vec<(String, String)>,
+        binary_cache: Option<PathBuf>,
+    ) -> Result<Self, ShaderError> {
+        let (program, files) =
+            unsafe { build_program(&stages, &defines, binary_cache.as_deref())? };
+
+        Ok(Self {
+            uniforms: unsafe { reflection::active_uniforms(program.id()) },
+            attributes: unsafe { reflection::active_attributes(program.id()) },
+            program,
+            stages,
+            defines,
+            binary_cache,
+            watched_files: watch_files(&files),
+            uniform_locations: RefCell::new(HashMap::new()),
+            uniform_block_bindings: RefCell::new(HashMap::new()),
+            storage_block_bindings: RefCell::new(HashMap::new()),
+            missing_uniform_policy: MissingUniformPolicy::default(),
+        })
+    }
+
+    /// Recompiles the program from its source files. On failure the current program is kept
+    /// and the compiler log is returned. Uniform values don't carry over to the new program,
+    /// but uniform and storage block bindings do.
+    pub fn reload(&mut self) -> Result<(), ShaderError> {
+        // Take in the current modification times first, so a broken edit is reported only once
+        let files: Vec<PathBuf> = self.watched_files.drain(..).map(|(path, _)| path).collect();
+        self.watched_files = watch_files(&files);
+
+        unsafe {
+            let (program, files) =
+                build_program(&self.stages, &self.defines, self.binary_cache.as_deref())?;
+
+            self.program = program;
+            self.watched_files = watch_files(&files);
+            self.uniforms = reflection::active_uniforms(self.program.id());
+            self.attributes = reflection::active_attributes(self.program.id());
+            self.uniform_locations.borrow_mut().clear();
+
+            for (block_name, &binding) in self.uniform_block_bindings.borrow().iter() {
+                if let Some(index) = self.uniform_block_index(block_name) {
+                    gl::UniformBlockBinding(self.program.id(), index, binding);
+                }
+            }
+            for (block_name, &binding) in self.storage_block_bindings.borrow().iter() {
+                if let Some(index) = self.storage_block_index(block_name) {
+                    gl::ShaderStorageBlockBinding(self.program.id(), index, binding);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reloads the program if any of its source files changed since it was last loaded,
+    /// printing the compiler log if the new sources fail to build. Meant to be polled once per
+    /// frame, and returns whether a new program was loaded.
+    pub fn reload_if_changed(&mut self) -> bool {
+        let changed = self
+            .watched_files
+            .iter()
+            .any(|(path, modified)| modified_time(path) != *modified);
+        if !changed {
+            return false;
+        }
+
+        match self.reload() {
+            Ok(()) => {
+                let paths: Vec<String> = self
+                    .stages
+                    .iter()
+                    .map(|(_, path)| path.display().to_string())
+                    .collect();
+                println!("Reloaded shader program from {}", paths.join(", "));
+                true
+            }
+            Err(e) => {
+                eprintln!("Failed to reload shader program, keeping the previous one:\n{e}");
+                false
+            }
+        }
+    }
+
+    pub fn use_program(&self) {
+        self.program.use_program();
+    }
+
+    pub fn get_uniform_location(&self, uniform_name: &str) -> GLint {
+        if let Some(&location) = self.uniform_locations.borrow().get(uniform_name) {
+            return location;
+        }
+
+        // A name with a NUL byte can't be in the program, same as any other unknown name
+        let location = match CString::new(uniform_name) {
+            Ok(name) => unsafe { gl::GetUniformLocation(self.program.id(), name.as_ptr()) },
+            Err(_) => -1,
+        };
+        self.uniform_locations
+            .borrow_mut()
+            .insert(uniform_name.to_owned(), location);
+
+        if location == -1 && self.missing_uniform_policy == MissingUniformPolicy::WarnOnce {
+            eprintln!("Warning: uniform `{uniform_name}` not found in shader program");
+        }
+        location
+    }
+
+    /// Uniforms left in the program after linking, excluding any the compiler optimized away.
+    pub fn active_uniforms(&self) -> &[ActiveUniform] {
+        &self.uniforms
+    }
+
+    pub fn active_attributes(&self) -> &[ActiveAttribute] {
+        &self.attributes
+    }
+
+    /// Looks up an active uniform by name, including elements of array uniforms like
+    /// `values[3]`.
+    pub fn find_uniform(&self, uniform_name: &str) -> Option<&ActiveUniform> {
+        self.uniforms
+            .iter()
+            .find(|uniform| uniform.matches(uniform_name))
+    }
+
+    pub fn find_attribute(&self, attribute_name: &str) -> Option<&ActiveAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == attribute_name)
+    }
+
+    /// Points the uniform block `block_name` at a binding point, where a `UniformBuffer` can
+    /// be bound for every program that uses the block.
+    pub fn bind_uniform_block(&self, block_name: &str, binding: u32) -> Result<(), ShaderError> {
+        let index = self
+            .uniform_block_index(block_name)
+            .ok_or_else(|| ShaderError::MissingUniformBlock(block_name.to_owned()))?;
+
+        unsafe { gl::UniformBlockBinding(self.program.id(), index, binding) };
+        self.uniform_block_bindings
+            .borrow_mut()
+            .insert(block_name.to_owned(), binding);
+        Ok(())
+    }
+
+    /// The minimum size in bytes of a buffer backing the uniform block `block_name`.
+    pub fn uniform_block_size(&self, block_name: &str) -> Option<usize> {
+        let index = self.uniform_block_index(block_name)?;
+
+        let mut size = 0;
+        unsafe {
+            gl::GetActiveUniformBlockiv(
+                self.program.id(),
+                index,
+                gl::UNIFORM_BLOCK_DATA_SIZE,
+                &mut size,
+            );
+        }
+        Some(size as usize)
+    }
+
+    fn uniform_block_index(&self, block_name: &str) -> Option<GLuint> {
+        let name = CString::new(block_name).ok()?;
+        let index = unsafe { gl::GetUniformBlockIndex(self.program.id(), name.as_ptr()) };
+        (index != gl::INVALID_INDEX).then_some(index)
+    }
+
+    /// Points the shader storage block `block_name` at a binding point. Needs OpenGL 4.3.
+    pub fn bind_storage_block(&self, block_name: &str, binding: u32) -> Result<(), ShaderError> {
+        let index = self
+            .storage_block_index(block_name)
+            .ok_or_else(|| ShaderError::MissingStorageBlock(block_name.to_owned()))?;
+
+        unsafe { gl::ShaderStorageBlockBinding(self.program.id(), index, binding) };
+        self.storage_block_bindings
+            .borrow_mut()
+            .insert(block_name.to_owned(), binding);
+        Ok(())
+    }
+
+    fn storage_block_index(&self, block_name: &str) -> Option<GLuint> {
+        let name = CString::new(block_name).ok()?;
+        let index = unsafe {
+            gl::GetProgramResourceIndex(self.program.id(), gl::SHADER_STORAGE_BLOCK, name.as_ptr())
+        };
+        (index != gl::INVALID_INDEX).then_some(index)
+    }
+
+    /// Checks that every name in `uniform_names` is an active uniform of the program, listing
+    /// the missing ones in the error otherwise.
+    pub fn validate_uniforms<I, S>(&self, uniform_names: I) -> Result<(), ShaderError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let missing: Vec<String> = uniform_names
+            .into_iter()
+            .filter(|name| self.find_uniform(name.as_ref()).is_none())
+            .map(|name| name.as_ref().to_owned())
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ShaderError::MissingUniforms(missing))
+        }
+    }
+
+    pub fn set_missing_uniform_policy(&mut self, policy: MissingUniformPolicy) {
+        self.missing_uniform_policy = policy;
+    }
+
+    /// Sets a uniform of the program, panicking if the uniform doesn't exist and the missing
+    /// uniform policy is `Error`. Like `glUniform*`, this only works while the program is in
+    /// use, so call `use_program` first.
+    pub fn set_uniform<U>(&self, uniform_name: &str, value: U)
+    where
+        U: Uniform,
+    {
+        if let Err(e) = self.try_set_uniform(uniform_name, value) {
+            panic!("{e}");
+        }
+    }
+
+    /// Sets a uniform of the program, which has to be in use already.
+    pub fn try_set_uniform<U>(&self, uniform_name: &str, value: U) -> Result<(), ShaderError>
+    where
+        U: Uniform,
+    {
+        let location = self.get_uniform_location(uniform_name);
+        if location == -1 {
+            return match self.missing_uniform_policy {
+                MissingUniformPolicy::Error => {
+                    Err(ShaderError::MissingUniforms(vec![uniform_name.to_owned()]))
+                }
+                _ => Ok(()),
+            };
+        }
+
+        unsafe { value.set(location) };
+        Ok(())
+    }
+}
+
+/// Preprocesses the source file of each stage and builds a program from them, returning it
+/// along with every file that went into it. With a `binary_cache` directory, a binary saved
+/// for the same sources is loaded instead when the driver accepts it.
+unsafe fn build_program(
+    stages: &[(ShaderStage, PathBuf)],
+    defines: &[(String, String)],
+    binary_cache: Option<&Path>,
+) -> Result<(Program, Vec<PathBuf>), ShaderError> {
+    let sources = stages
+        .iter()
+        .map(|(stage, path)| Ok((*stage, PreprocessedSource::load(path, defines)?)))
+        .collect::<Result<Vec<_>, ShaderError>>()?;
+
+    let cache = binary_cache.and_then(|dir| ProgramBinaryCache::new(dir, &sources));
+    let program = match cache.as_ref().and_then(|cache| cache.load()) {
+        Some(program) => program,
+        None => {
+            let program = compile_program(&sources, cache.is_some())?;
+            if let Some(cache) = &cache {
+                cache.store(program);
+            }
+            program
+        }
+    };
+    let program = Program::from_id(program);
+    let files = sources
+        .into_iter()
+        .flat_map(|(_, source)| source.files)
+        .collect();
+    Ok((program, files))
+}
+
+/// Compiles each preprocessed stage and links them into a program. `retrievable` asks the
+/// driver to keep the program's binary around for `glGetProgramBinary`.
+unsafe fn compile_program(
+    sources: &[(ShaderStage, PreprocessedSource)],
+    retrievable: bool,
+) -> Result<GLuint, ShaderError> {
+    let mut shaders = Vec::new();
+    let mut failures = Vec::new();
+
+    for (stage, source) in sources {
+        let source_cstr = match CString::new(source.text.as_str()) {
+            Ok(source_cstr) => source_cstr,
+            Err(_) => {
+                // Only injected defines can get here, the files themselves are checked for NULs
+                for shader in shaders {
+                    gl::DeleteShader(shader);
+                }
+                return Err(ShaderError::InvalidSource {
+                    path: source.files[0].clone(),
+                    line: None,
+                    message: "Defines contain a NUL byte".to_owned(),
+                });
+            }
+        };
+
+        // Create and compile shader
+        let shader = gl::CreateShader(stage.to_gl());
+        gl::ShaderSource(shader, 1, &source_cstr.as_ptr(), std::ptr::null());
+        gl::CompileShader(shader);
+        shaders.push(shader);
+
+        let mut success = 0;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+
+        // Check for compilation errors
+        if success == 0 {
+            let log = shader_info_log(shader);
+            failures.push(CompileFailure {
+                stage: *stage,
+                path: source.files[0].clone(),
+                diagnostics: source.diagnostics(&log),
+                log,
+            });
+        }
+    }
+
+    // Report every stage that failed to compile
+    if !failures.is_empty() {
+        for shader in shaders {
+            gl::DeleteShader(shader);
+        }
+        return Err(ShaderError::Compile(failures));
+    }
+
+    // Create and link shader program
+    let program = gl::CreateProgram();
+    for &shader in &shaders {
+        gl::AttachShader(program, shader);
+    }
+    if retrievable {
+        gl::ProgramParameteri(
+            program,
+            gl::PROGRAM_BINARY_RETRIEVABLE_HINT,
+            gl::TRUE as i32,
+        );
+    }
+    gl::LinkProgram(program);
+
+    // Delete unneeded shaders
+    for shader in shaders {
+        gl::DeleteShader(shader);
+    }
+
+    let mut link_success = 0;
+    gl::GetProgramiv(program, gl::LINK_STATUS, &mut link_success);
+
+    // Check for linking errors
+    if link_success == 0 {
+        let log = program_info_log(program);
+        gl::DeleteProgram(program);
+
+        // Link logs can refer to any of the stages, so their locations aren't mapped
+        let diagnostics = log
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| ShaderDiagnostic {
+                file: None,
+                line: None,
+                message: line.trim().to_owned(),
+            })
+            .collect();
+        Err(ShaderError::Link { log, diagnostics })
+    } else {
+        Ok(program)
+    }
+}
+
+unsafe fn shader_info_log(shader: GLuint) -> String {
+    let mut length = 0;
+    gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut length);
+
+    let mut info_buffer = vec![0u8; length.max(1) as usize];
+    let mut written = 0;
+    gl::GetShaderInfoLog(
+        shader,
+        info_buffer.len() as i32,
+        &mut written,
+        info_buffer.as_mut_ptr() as *mut GLchar,
+    );
+    info_buffer.truncate(written as usize);
+    String::from_utf8_lossy(&info_buffer).into_owned()
+}
+
+unsafe fn program_info_log(program: GLuint) -> String {
+    let mut length = 0;
+    gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut length);
+
+    let mut info_buffer = vec![0u8; length.max(1) as usize];
+    let mut written = 0;
+    gl::GetProgramInfoLog(
+        program,
+        info_buffer.len() as i32,
+        &mut written,
+        info_buffer.as_mut_ptr() as *mut GLchar,
+    );
+    info_buffer.truncate(written as usize);
+    String::from_utf8_lossy(&info_buffer).into_owned()
+}
+
+fn watch_files(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    paths
+        .iter()
+        .map(|path| (path.clone(), modified_time(path)))
+        .collect()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
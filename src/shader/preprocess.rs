@@ -0,0 +1,430 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use super::{ShaderDiagnostic, ShaderError};
+
+/// GLSL source with `#include`s resolved and defines injected, remembering which file and
+/// line each of its lines came from.
+pub(super) struct PreprocessedSource {
+    pub text: String,
+    /// Every file that went into the source, starting with the root file.
+    pub files: Vec<PathBuf>,
+    canonical_files: Vec<PathBuf>,
+    /// File index and 1-based line of each output line. Injected lines have no origin.
+    line_origins: Vec<Option<(usize, usize)>>,
+}
+
+impl PreprocessedSource {
+    /// Reads `path`, replacing each `#include "file.glsl"` line with the contents of that file,
+    /// resolved relative to the including file. Files already included are skipped, so shared
+    /// struct and function definitions can be included from several places. `defines` are
+    /// inserted right after the `#version` line, or at the start without one.
+    pub fn load(path: &Path, defines: &[(String, String)]) -> Result<Self, ShaderError> {
+        let mut source = Self {
+            text: String::new(),
+            files: Vec::new(),
+            canonical_files: Vec::new(),
+            line_origins: Vec::new(),
+        };
+        let mut include_stack = Vec::new();
+        source.append_file(path, defines, &mut include_stack)?;
+
+        Ok(source)
+    }
+
+    /// Splits a compiler log into one diagnostic per line, mapping the `source:line` location
+    /// each starts with back to the original file and line.
+    pub fn diagnostics(&self, log: &str) -> Vec<ShaderDiagnostic> {
+        log.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let Some((range, source_line)) = log_line_location(line) else {
+                    return ShaderDiagnostic {
+                        file: None,
+                        line: None,
+                        message: line.trim().to_owned(),
+                    };
+                };
+
+                // Drop the column Mesa puts after the line and the separator before the message
+                let rest = &line[range.end..];
+                let rest = rest
+                    .strip_prefix('(')
+                    .and_then(|rest| rest.split_once(')'))
+                    .map_or(rest, |(_, rest)| rest);
+                let rest = rest.trim_start().trim_start_matches(':').trim();
+                let message = match line[..range.start].trim() {
+                    "" => rest.to_owned(),
+                    prefix => format!("{prefix} {rest}"),
+                };
+
+                let (file, line) = match self.origin(source_line) {
+                    Some((path, line)) => (Some(path.to_path_buf()), line),
+                    None => (None, source_line),
+                };
+                ShaderDiagnostic {
+                    file,
+                    line: Some(line),
+                    message,
+                }
+            })
+            .collect()
+    }
+
+    /// The file and line that 1-based line `line` of the output came from.
+    pub fn origin(&self, line: usize) -> Option<(&Path, usize)> {
+        let (file, original_line) = (*self.line_origins.get(line.checked_sub(1)?)?)?;
+        Some((&self.files[file], original_line))
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        defines: &[(String, String)],
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), ShaderError> {
+        let io_error = |error| ShaderError::Io {
+            path: path.to_path_buf(),
+            error,
+        };
+        let canonical = path.canonicalize().map_err(io_error)?;
+
+        if include_stack.contains(&canonical) {
+            let cycle: Vec<String> = include_stack
+                .iter()
+                .chain([&canonical])
+                .map(|path| path.display().to_string())
+                .collect();
+            return Err(ShaderError::InvalidSource {
+                path: path.to_path_buf(),
+                line: None,
+                message: format!("Include cycle: {}", cycle.join(" -> ")),
+            });
+        }
+        if self.canonical_files.contains(&canonical) {
+            return Ok(());
+        }
+
+        let text = std::fs::read_to_string(path).map_err(|error| match error.kind() {
+            ErrorKind::InvalidData => ShaderError::InvalidSource {
+                path: path.to_path_buf(),
+                line: None,
+                message: "Source is not valid UTF-8".to_owned(),
+            },
+            _ => io_error(error),
+        })?;
+        let file = self.files.len();
+        self.files.push(path.to_path_buf());
+        self.canonical_files.push(canonical.clone());
+        include_stack.push(canonical);
+
+        let mut defines_pending = include_stack.len() == 1;
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+
+            if line.contains('\0') {
+                return Err(ShaderError::InvalidSource {
+                    path: path.to_path_buf(),
+                    line: Some(line_number),
+                    message: "Source contains a NUL byte".to_owned(),
+                });
+            }
+
+            if let Some(include) = include_path(line) {
+                let include = include.map_err(|message| ShaderError::InvalidSource {
+                    path: path.to_path_buf(),
+                    line: Some(line_number),
+                    message,
+                })?;
+                let include_path = path.parent().unwrap_or(Path::new("")).join(include);
+                self.append_file(&include_path, &[], include_stack)?;
+                continue;
+            }
+
+            self.push_line(line, Some((file, line_number)));
+
+            // Defines have to come after `#version`, which has to be the first statement
+            if defines_pending && line.trim_start().starts_with("#version") {
+                self.push_defines(defines);
+                defines_pending = false;
+            }
+        }
+        // Without a `#version` line the defines go first so the code after them sees them
+        if defines_pending {
+            let lines: String = defines
+                .iter()
+                .map(|(name, value)| define(name, value) + "\n")
+                .collect();
+            self.text.insert_str(0, &lines);
+            self.line_origins.splice(0..0, defines.iter().map(|_| None));
+        }
+
+        include_stack.pop();
+        Ok(())
+    }
+
+    fn push_defines(&mut self, defines: &[(String, String)]) {
+        for (name, value) in defines {
+            self.push_line(&define(name, value), None);
+        }
+    }
+
+    fn push_line(&mut self, line: &str, origin: Option<(usize, usize)>) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        self.line_origins.push(origin);
+    }
+}
+
+fn define(name: &str, value: &str) -> String {
+    format!("#define {name} {value}")
+}
+
+/// The path of an `#include "path"` line, or an error if the directive is malformed.
+fn include_path(line: &str) -> Option<Result<&str, String>> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("include")?.trim();
+
+    let path = rest
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| format!("Expected `#include \"file\"`, found `{}`", line.trim()));
+    Some(path)
+}
+
+/// Finds the location at the start of a compiler log line, returning its byte range and line
+/// number. Handles the `0:12(5):` (Mesa), `0(12) :` (NVIDIA) and `ERROR: 0:12:` (AMD, Intel)
+/// formats.
+pub(super) fn log_line_location(line: &str) -> Option<(std::ops::Range<usize>, usize)> {
+    let start = ["ERROR: ", "WARNING: "]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix).map(|_| prefix.len()))
+        .unwrap_or(0);
+    let rest = &line[start..];
+
+    let source_len = rest.find(|c: char| !c.is_ascii_digit())?;
+    if source_len == 0 {
+        return None;
+    }
+    let (separator, closing) = match rest[source_len..].chars().next()? {
+        ':' => (':', None),
+        '(' => ('(', Some(')')),
+        _ => return None,
+    };
+
+    let line_start = source_len + separator.len_utf8();
+    let line_len = rest[line_start..].find(|c: char| !c.is_ascii_digit())?;
+    let line_number = rest[line_start..line_start + line_len].parse().ok()?;
+
+    let mut end = line_start + line_len;
+    if let Some(closing) = closing {
+        if !rest[end..].starts_with(closing) {
+            return None;
+        }
+        end += closing.len_utf8();
+    }
+
+    Some((start..start + end, line_number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `files` into a fresh directory for `test` under the system temp directory.
+    fn write_files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("learn_opengl_preprocess_{}", std::process::id()))
+            .join(test);
+        let _ = std::fs::remove_dir_all(&dir);
+        for (path, contents) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    fn location(line: &str) -> Option<(&str, usize)> {
+        log_line_location(line).map(|(range, line_number)| (&line[range], line_number))
+    }
+
+    #[test]
+    fn log_formats() {
+        assert_eq!(
+            location("0:12(3): error: `x' undeclared"),
+            Some(("0:12", 12))
+        );
+        assert_eq!(
+            location("0(12) : error C1008: undefined variable \"x\""),
+            Some(("0(12)", 12))
+        );
+        assert_eq!(
+            location("ERROR: 0:12: 'x' : undeclared identifier"),
+            Some(("0:12", 12))
+        );
+        assert_eq!(location("WARNING: 0:7: 'y' : unused"), Some(("0:7", 7)));
+
+        assert_eq!(location("error: linking failed"), None);
+        assert_eq!(location("0(12 : missing paren"), None);
+        assert_eq!(location(":12: no source"), None);
+    }
+
+    #[test]
+    fn nested_includes_map_back_to_their_files() {
+        let dir = write_files(
+            "nested",
+            &[
+                (
+                    "main.glsl",
+                    "#version 330 core\n#include \"lib/lighting.glsl\"\nvoid main() {}\n",
+                ),
+                (
+                    "lib/lighting.glsl",
+                    "// lighting\n#include \"math.glsl\"\nvec3 light();\n",
+                ),
+                (
+                    "lib/math.glsl",
+                    "float square(float x);\nfloat cube(float x);\n",
+                ),
+            ],
+        );
+        let defines = [("LIGHTS".to_owned(), "4".to_owned())];
+        let source = PreprocessedSource::load(&dir.join("main.glsl"), &defines).unwrap();
+
+        assert_eq!(
+            source.text,
+            "#version 330 core\n\
+             #define LIGHTS 4\n\
+             // lighting\n\
+             float square(float x);\n\
+             float cube(float x);\n\
+             vec3 light();\n\
+             void main() {}\n"
+        );
+
+        let main = dir.join("main.glsl");
+        let lighting = dir.join("lib/lighting.glsl");
+        let math = dir.join("lib/math.glsl");
+        assert_eq!(source.files, [main.clone(), lighting.clone(), math.clone()]);
+
+        assert_eq!(source.origin(1), Some((main.as_path(), 1)));
+        assert_eq!(source.origin(2), None);
+        assert_eq!(source.origin(3), Some((lighting.as_path(), 1)));
+        assert_eq!(source.origin(5), Some((math.as_path(), 2)));
+        assert_eq!(source.origin(6), Some((lighting.as_path(), 3)));
+        assert_eq!(source.origin(7), Some((main.as_path(), 3)));
+        assert_eq!(source.origin(0), None);
+        assert_eq!(source.origin(8), None);
+
+        let log = "0:5(7): error: syntax error\n\
+                   0(6) : error C0000: unknown type\n\
+                   \n\
+                   error: linking failed\n";
+        assert_eq!(
+            source.diagnostics(log),
+            [
+                ShaderDiagnostic {
+                    file: Some(math),
+                    line: Some(2),
+                    message: "error: syntax error".to_owned(),
+                },
+                ShaderDiagnostic {
+                    file: Some(lighting),
+                    line: Some(3),
+                    message: "error C0000: unknown type".to_owned(),
+                },
+                ShaderDiagnostic {
+                    file: None,
+                    line: None,
+                    message: "error: linking failed".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn defines_go_first_without_a_version_line() {
+        let dir = write_files(
+            "no_version",
+            &[
+                ("main.glsl", "#include \"lib.glsl\"\nvoid main() {}\n"),
+                ("lib.glsl", "float value = LIGHTS;\n"),
+            ],
+        );
+        let defines = [("LIGHTS".to_owned(), "4".to_owned())];
+        let source = PreprocessedSource::load(&dir.join("main.glsl"), &defines).unwrap();
+
+        assert_eq!(
+            source.text,
+            "#define LIGHTS 4\nfloat value = LIGHTS;\nvoid main() {}\n"
+        );
+        assert_eq!(source.origin(1), None);
+        assert_eq!(source.origin(2), Some((dir.join("lib.glsl").as_path(), 1)));
+        assert_eq!(source.origin(3), Some((dir.join("main.glsl").as_path(), 2)));
+    }
+
+    #[test]
+    fn files_are_included_once() {
+        let dir = write_files(
+            "once",
+            &[
+                (
+                    "main.glsl",
+                    "#version 330 core\n#include \"a.glsl\"\n#include \"b.glsl\"\n",
+                ),
+                ("a.glsl", "#include \"shared.glsl\"\nfloat a;\n"),
+                ("b.glsl", "#include \"./shared.glsl\"\nfloat b;\n"),
+                ("shared.glsl", "struct Light { vec3 position; };\n"),
+            ],
+        );
+        let source = PreprocessedSource::load(&dir.join("main.glsl"), &[]).unwrap();
+
+        assert_eq!(
+            source.text,
+            "#version 330 core\nstruct Light { vec3 position; };\nfloat a;\nfloat b;\n"
+        );
+        assert_eq!(source.files.len(), 4);
+    }
+
+    #[test]
+    fn include_cycles_are_errors() {
+        let dir = write_files(
+            "cycle",
+            &[
+                ("main.glsl", "#version 330 core\n#include \"a.glsl\"\n"),
+                ("a.glsl", "#include \"b.glsl\"\n"),
+                ("b.glsl", "\n#include \"a.glsl\"\n"),
+            ],
+        );
+
+        match PreprocessedSource::load(&dir.join("main.glsl"), &[]) {
+            Err(ShaderError::InvalidSource { path, message, .. }) => {
+                assert_eq!(path, dir.join("a.glsl"));
+                assert!(message.starts_with("Include cycle: "), "{message}");
+                // main -> a -> b -> a
+                assert_eq!(message.matches(" -> ").count(), 3);
+                assert!(message.ends_with("a.glsl"), "{message}");
+            }
+            Err(other) => panic!("expected an include cycle, got {other}"),
+            Ok(_) => panic!("expected an include cycle"),
+        }
+    }
+
+    #[test]
+    fn malformed_includes_report_their_line() {
+        let dir = write_files(
+            "malformed",
+            &[("main.glsl", "#version 330 core\n\n#include <lib.glsl>\n")],
+        );
+
+        match PreprocessedSource::load(&dir.join("main.glsl"), &[]) {
+            Err(ShaderError::InvalidSource { line, .. }) => assert_eq!(line, Some(3)),
+            Err(other) => panic!("expected an invalid include, got {other}"),
+            Ok(_) => panic!("expected an invalid include"),
+        }
+    }
+}
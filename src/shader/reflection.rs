@@ -0,0 +1,119 @@
+use std::ffi::CString;
+
+use gl::types::{GLchar, GLenum, GLint, GLuint};
+
+/// An active uniform of a linked program, as reported by `glGetActiveUniform`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveUniform {
+    /// Array uniforms are reported by their first element, e.g. `values[0]`. Arrays of structs
+    /// get one entry per member of each element instead, e.g. `pointLights[2].position`.
+    pub name: String,
+    pub gl_type: GLenum,
+    /// Number of array elements, or 1 for non-array uniforms.
+    pub size: i32,
+    /// -1 for uniforms inside uniform blocks, which have no location.
+    pub location: GLint,
+}
+
+impl ActiveUniform {
+    /// Whether `name` refers to this uniform or one of its array elements.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.name == name {
+            return true;
+        }
+
+        // Array uniforms can also be referred to by their bare name or any valid index
+        let Some(base) = self.name.strip_suffix("[0]") else {
+            return false;
+        };
+        let Some(rest) = name.strip_prefix(base) else {
+            return false;
+        };
+        if rest.is_empty() {
+            return true;
+        }
+        rest.strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|index| index.parse::<i32>().ok())
+            .is_some_and(|index| index < self.size)
+    }
+}
+
+/// An active vertex attribute of a linked program, as reported by `glGetActiveAttrib`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveAttribute {
+    pub name: String,
+    pub gl_type: GLenum,
+    pub size: i32,
+    /// -1 for built-in attributes like `gl_VertexID`.
+    pub location: GLint,
+}
+
+pub(super) unsafe fn active_uniforms(program: GLuint) -> Vec<ActiveUniform> {
+    let mut count = 0;
+    gl::GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+    let mut max_length = 0;
+    gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+
+    (0..count as GLuint)
+        .map(|index| {
+            let (name, gl_type, size) =
+                active_variable(max_length, |buffer_size, length, size, gl_type, name| {
+                    gl::GetActiveUniform(program, index, buffer_size, length, size, gl_type, name)
+                });
+            let name_cstr = CString::new(name.as_str()).unwrap();
+            let location = gl::GetUniformLocation(program, name_cstr.as_ptr());
+
+            ActiveUniform {
+                name,
+                gl_type,
+                size,
+                location,
+            }
+        })
+        .collect()
+}
+
+pub(super) unsafe fn active_attributes(program: GLuint) -> Vec<ActiveAttribute> {
+    let mut count = 0;
+    gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTES, &mut count);
+    let mut max_length = 0;
+    gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+
+    (0..count as GLuint)
+        .map(|index| {
+            let (name, gl_type, size) =
+                active_variable(max_length, |buffer_size, length, size, gl_type, name| {
+                    gl::GetActiveAttrib(program, index, buffer_size, length, size, gl_type, name)
+                });
+            let name_cstr = CString::new(name.as_str()).unwrap();
+            let location = gl::GetAttribLocation(program, name_cstr.as_ptr());
+
+            ActiveAttribute {
+                name,
+                gl_type,
+                size,
+                location,
+            }
+        })
+        .collect()
+}
+
+/// Calls one of the `glGetActive*` queries and returns the variable's name, type and size.
+unsafe fn active_variable<F>(max_length: GLint, query: F) -> (String, GLenum, i32)
+where
+    F: FnOnce(GLint, *mut GLint, *mut GLint, *mut GLenum, *mut GLchar),
+{
+    let mut buffer = vec![0u8; max_length.max(1) as usize];
+    let (mut length, mut size, mut gl_type) = (0, 0, 0);
+    query(
+        buffer.len() as GLint,
+        &mut length,
+        &mut size,
+        &mut gl_type,
+        buffer.as_mut_ptr() as *mut GLchar,
+    );
+    buffer.truncate(length as usize);
+
+    (String::from_utf8_lossy(&buffer).into_owned(), gl_type, size)
+}
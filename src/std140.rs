@@ -0,0 +1,363 @@
+use nalgebra_glm as glm;
+
+/// A value with a std140 layout, the layout of `layout(std140)` uniform blocks. Structs get it
+/// through `std140_struct!`.
+pub trait Std140 {
+    /// Alignment of the value as a block member.
+    const ALIGN: usize;
+    /// Size of the value, without padding up to the next member.
+    const SIZE: usize;
+
+    /// Writes the value into `out`, which is `SIZE` bytes long. Padding is left untouched.
+    fn write_std140(&self, out: &mut [u8]);
+
+    fn to_std140_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; Self::SIZE];
+        self.write_std140(&mut bytes);
+        bytes
+    }
+}
+
+/// Implements `Std140` for scalars and vectors, which are stored as their `f32`/`i32`/`u32`
+/// components.
+macro_rules! impl_std140_components {
+    ($($ty:ty => $align:expr),* $(,)?) => {
+        $(
+            impl Std140 for $ty {
+                const ALIGN: usize = $align;
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn write_std140(&self, out: &mut [u8]) {
+                    let components = std::slice::from_ref(self);
+                    // SAFETY: these types are plain 4-byte components without padding
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(components.as_ptr() as *const u8, Self::SIZE)
+                    };
+                    out.copy_from_slice(bytes);
+                }
+            }
+        )*
+    };
+}
+
+impl_std140_components!(
+    f32 => 4,
+    i32 => 4,
+    u32 => 4,
+    glm::Vec2 => 8,
+    glm::Vec3 => 16,
+    glm::Vec4 => 16,
+    glm::IVec2 => 8,
+    glm::IVec3 => 16,
+    glm::IVec4 => 16,
+);
+
+impl Std140 for bool {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        (*self as u32).write_std140(out);
+    }
+}
+
+/// Matrices are stored as arrays of column vectors, each padded to a `vec4`.
+impl Std140 for glm::Mat3 {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 48;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        for (i, column) in self.column_iter().enumerate() {
+            glm::Vec3::from(column).write_std140(&mut out[i * 16..i * 16 + 12]);
+        }
+    }
+}
+
+impl Std140 for glm::Mat4 {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 64;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        for (i, column) in self.column_iter().enumerate() {
+            glm::Vec4::from(column).write_std140(&mut out[i * 16..i * 16 + 16]);
+        }
+    }
+}
+
+/// Array elements are padded to a multiple of 16 bytes each.
+impl<T, const N: usize> Std140 for [T; N]
+where
+    T: Std140,
+{
+    const ALIGN: usize = 16;
+    const SIZE: usize = T::SIZE.div_ceil(16) * 16 * N;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        let stride = T::SIZE.div_ceil(16) * 16;
+        for (i, element) in self.iter().enumerate() {
+            let offset = i * stride;
+            element.write_std140(&mut out[offset..offset + T::SIZE]);
+        }
+    }
+}
+
+/// Declares a struct and implements `Std140` for it, laying its fields out as the matching
+/// GLSL struct in a `layout(std140)` block. Fields have to implement `Std140` themselves.
+///
+/// ```ignore
+/// std140_struct! {
+///     pub struct PointLight {
+///         pub position: glm::Vec3,
+///         pub constant: f32,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! std140_struct {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::Std140 for $name {
+            // Structs are aligned like a vec4, whatever their members
+            const ALIGN: usize = 16;
+            const SIZE: usize = {
+                let mut offset: usize = 0;
+                $(
+                    let align = <$ty as $crate::Std140>::ALIGN;
+                    offset = offset.div_ceil(align) * align + <$ty as $crate::Std140>::SIZE;
+                )*
+                offset.div_ceil(16) * 16
+            };
+
+            fn write_std140(&self, out: &mut [u8]) {
+                let mut offset: usize = 0;
+                $(
+                    let align = <$ty as $crate::Std140>::ALIGN;
+                    let size = <$ty as $crate::Std140>::SIZE;
+                    offset = offset.div_ceil(align) * align;
+                    $crate::Std140::write_std140(&self.$field, &mut out[offset..offset + size]);
+                    offset += size;
+                )*
+                let _ = offset;
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the `f32` written at byte `offset`.
+    fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    // The lights of the section 17 `Lights` uniform block
+    std140_struct! {
+        struct DirLight {
+            direction: glm::Vec3,
+            ambient: glm::Vec3,
+            diffuse: glm::Vec3,
+            specular: glm::Vec3,
+        }
+    }
+
+    std140_struct! {
+        #[derive(Clone, Copy)]
+        struct PointLight {
+            position: glm::Vec3,
+            ambient: glm::Vec3,
+            diffuse: glm::Vec3,
+            specular: glm::Vec3,
+            constant: f32,
+            linear: f32,
+            quadratic: f32,
+        }
+    }
+
+    std140_struct! {
+        struct Spotlight {
+            position: glm::Vec3,
+            direction: glm::Vec3,
+            inner_cutoff: f32,
+            outer_cutoff: f32,
+            ambient: glm::Vec3,
+            diffuse: glm::Vec3,
+            specular: glm::Vec3,
+            constant: f32,
+            linear: f32,
+            quadratic: f32,
+        }
+    }
+
+    std140_struct! {
+        struct Lights {
+            dir_light: DirLight,
+            point_lights: [PointLight; 4],
+            spotlight: Spotlight,
+        }
+    }
+
+    fn point_light(n: f32) -> PointLight {
+        PointLight {
+            position: glm::vec3(n, n, n),
+            ambient: glm::vec3(n + 0.1, 0.0, 0.0),
+            diffuse: glm::vec3(n + 0.2, 0.0, 0.0),
+            specular: glm::vec3(n + 0.3, 0.0, 0.0),
+            constant: n + 0.4,
+            linear: n + 0.5,
+            quadratic: n + 0.6,
+        }
+    }
+
+    #[test]
+    fn scalars_and_vectors() {
+        assert_eq!((f32::ALIGN, f32::SIZE), (4, 4));
+        assert_eq!((bool::ALIGN, bool::SIZE), (4, 4));
+        assert_eq!((glm::Vec2::ALIGN, glm::Vec2::SIZE), (8, 8));
+        assert_eq!((glm::Vec3::ALIGN, glm::Vec3::SIZE), (16, 12));
+        assert_eq!((glm::Vec4::ALIGN, glm::Vec4::SIZE), (16, 16));
+        assert_eq!(true.to_std140_bytes(), 1u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn float_arrays_have_a_16_byte_stride() {
+        assert_eq!(<[f32; 3]>::ALIGN, 16);
+        assert_eq!(<[f32; 3]>::SIZE, 48);
+        assert_eq!(<[glm::Vec3; 2]>::SIZE, 32);
+        assert_eq!(<[glm::Vec4; 2]>::SIZE, 32);
+
+        let bytes = [1.0f32, 2.0, 3.0].to_std140_bytes();
+        assert_eq!(f32_at(&bytes, 0), 1.0);
+        assert_eq!(f32_at(&bytes, 16), 2.0);
+        assert_eq!(f32_at(&bytes, 32), 3.0);
+        assert_eq!(f32_at(&bytes, 4), 0.0);
+    }
+
+    #[test]
+    fn mat3_columns_are_padded_to_vec4() {
+        assert_eq!(glm::Mat3::ALIGN, 16);
+        assert_eq!(glm::Mat3::SIZE, 48);
+        assert_eq!(glm::Mat4::SIZE, 64);
+
+        let matrix = glm::mat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0);
+        let bytes = matrix.to_std140_bytes();
+        for column in 0..3 {
+            for row in 0..3 {
+                let offset = column * 16 + row * 4;
+                assert_eq!(f32_at(&bytes, offset), matrix[(row, column)]);
+            }
+            assert_eq!(f32_at(&bytes, column * 16 + 12), 0.0);
+        }
+    }
+
+    #[test]
+    fn struct_size_rounds_up_to_16() {
+        std140_struct! {
+            struct Scalar {
+                value: f32,
+            }
+        }
+        std140_struct! {
+            struct ScalarAfterVec3 {
+                direction: glm::Vec3,
+                intensity: f32,
+            }
+        }
+        std140_struct! {
+            struct Vec3AfterScalar {
+                intensity: f32,
+                direction: glm::Vec3,
+            }
+        }
+
+        assert_eq!(Scalar::SIZE, 16);
+        assert_eq!(Scalar::ALIGN, 16);
+
+        // A scalar fits in the padding after a vec3, but a vec3 after a scalar doesn't
+        let bytes = ScalarAfterVec3 {
+            direction: glm::vec3(1.0, 2.0, 3.0),
+            intensity: 4.0,
+        }
+        .to_std140_bytes();
+        assert_eq!(ScalarAfterVec3::SIZE, 16);
+        assert_eq!(f32_at(&bytes, 12), 4.0);
+
+        let bytes = Vec3AfterScalar {
+            intensity: 4.0,
+            direction: glm::vec3(1.0, 2.0, 3.0),
+        }
+        .to_std140_bytes();
+        assert_eq!(Vec3AfterScalar::SIZE, 32);
+        assert_eq!(f32_at(&bytes, 0), 4.0);
+        assert_eq!(f32_at(&bytes, 16), 1.0);
+    }
+
+    #[test]
+    fn point_light_layout() {
+        assert_eq!(PointLight::SIZE, 80);
+
+        let bytes = point_light(1.0).to_std140_bytes();
+        assert_eq!(f32_at(&bytes, 0), 1.0);
+        assert_eq!(f32_at(&bytes, 16), 1.1);
+        assert_eq!(f32_at(&bytes, 32), 1.2);
+        assert_eq!(f32_at(&bytes, 48), 1.3);
+        // The scalars pack into the padding after `specular`
+        assert_eq!(f32_at(&bytes, 60), 1.4);
+        assert_eq!(f32_at(&bytes, 64), 1.5);
+        assert_eq!(f32_at(&bytes, 68), 1.6);
+    }
+
+    #[test]
+    fn lights_block() {
+        assert_eq!(DirLight::SIZE, 64);
+        assert_eq!(Spotlight::SIZE, 112);
+        assert_eq!(Lights::SIZE, 64 + 4 * 80 + 112);
+
+        let lights = Lights {
+            dir_light: DirLight {
+                direction: glm::vec3(-1.0, 0.0, 0.0),
+                ambient: glm::Vec3::zeros(),
+                diffuse: glm::Vec3::zeros(),
+                specular: glm::vec3(0.5, 0.0, 0.0),
+            },
+            point_lights: [1.0, 2.0, 3.0, 4.0].map(point_light),
+            spotlight: Spotlight {
+                position: glm::vec3(10.0, 0.0, 0.0),
+                direction: glm::vec3(11.0, 0.0, 0.0),
+                inner_cutoff: 12.0,
+                outer_cutoff: 13.0,
+                ambient: glm::vec3(14.0, 0.0, 0.0),
+                diffuse: glm::vec3(15.0, 0.0, 0.0),
+                specular: glm::vec3(16.0, 0.0, 0.0),
+                constant: 17.0,
+                linear: 18.0,
+                quadratic: 19.0,
+            },
+        };
+        let bytes = lights.to_std140_bytes();
+
+        assert_eq!(f32_at(&bytes, 0), -1.0);
+        assert_eq!(f32_at(&bytes, 48), 0.5);
+        for i in 0..4 {
+            let offset = 64 + i * 80;
+            assert_eq!(f32_at(&bytes, offset), i as f32 + 1.0);
+            assert_eq!(f32_at(&bytes, offset + 68), i as f32 + 1.6);
+        }
+
+        let spotlight = 64 + 4 * 80;
+        let offsets = [0, 16, 28, 32, 48, 64, 80, 92, 96, 100];
+        for (value, offset) in (10..).zip(offsets) {
+            assert_eq!(f32_at(&bytes, spotlight + offset), value as f32);
+        }
+    }
+}
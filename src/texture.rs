@@ -0,0 +1,337 @@
+use std::{
+    ffi::c_void,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use gl::types::{GLenum, GLuint};
+use image::{io::Reader as ImageReader, DynamicImage};
+
+use crate::{objects::Texture, GlContext};
+
+#[derive(Debug)]
+pub enum TextureError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Decode {
+        path: PathBuf,
+        error: image::ImageError,
+    },
+    TooLarge {
+        width: u32,
+        height: u32,
+    },
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Decode { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::TooLarge { width, height } => {
+                write!(f, "{width}x{height} image is too large for a texture")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureWrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+impl TextureWrap {
+    fn to_gl(self) -> GLenum {
+        match self {
+            TextureWrap::Repeat => gl::REPEAT,
+            TextureWrap::MirroredRepeat => gl::MIRRORED_REPEAT,
+            TextureWrap::ClampToEdge => gl::CLAMP_TO_EDGE,
+            TextureWrap::ClampToBorder => gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+
+impl TextureFilter {
+    fn to_gl(self) -> GLenum {
+        match self {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR,
+            TextureFilter::NearestMipmapNearest => gl::NEAREST_MIPMAP_NEAREST,
+            TextureFilter::LinearMipmapNearest => gl::LINEAR_MIPMAP_NEAREST,
+            TextureFilter::NearestMipmapLinear => gl::NEAREST_MIPMAP_LINEAR,
+            TextureFilter::LinearMipmapLinear => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    /// The filter to use for magnification, where mipmaps never apply.
+    fn without_mipmaps(self) -> Self {
+        match self {
+            TextureFilter::Nearest
+            | TextureFilter::NearestMipmapNearest
+            | TextureFilter::NearestMipmapLinear => TextureFilter::Nearest,
+            _ => TextureFilter::Linear,
+        }
+    }
+}
+
+/// Pixel layout of an image as uploaded to OpenGL.
+struct PixelFormat {
+    internal_format: GLenum,
+    format: GLenum,
+    pixel_type: GLenum,
+    /// Spreads luminance images over the RGB channels, as the image crate reads them.
+    swizzle: Option<[GLenum; 4]>,
+}
+
+impl PixelFormat {
+    /// Picks the formats for uploading `img`, converting it first if OpenGL can't take its
+    /// pixel layout directly.
+    fn prepare(img: DynamicImage, srgb: bool) -> (DynamicImage, Self) {
+        let rgb8 = if srgb { gl::SRGB8 } else { gl::RGB8 };
+        let rgba8 = if srgb { gl::SRGB8_ALPHA8 } else { gl::RGBA8 };
+        let luma = Some([gl::RED, gl::RED, gl::RED, gl::ONE]);
+        let luma_alpha = Some([gl::RED, gl::RED, gl::RED, gl::GREEN]);
+
+        let (internal_format, format, pixel_type, swizzle) = match &img {
+            DynamicImage::ImageLuma8(_) => (gl::R8, gl::RED, gl::UNSIGNED_BYTE, luma),
+            DynamicImage::ImageLumaA8(_) => (gl::RG8, gl::RG, gl::UNSIGNED_BYTE, luma_alpha),
+            DynamicImage::ImageRgb8(_) => (rgb8, gl::RGB, gl::UNSIGNED_BYTE, None),
+            DynamicImage::ImageRgba8(_) => (rgba8, gl::RGBA, gl::UNSIGNED_BYTE, None),
+            DynamicImage::ImageLuma16(_) => (gl::R16, gl::RED, gl::UNSIGNED_SHORT, luma),
+            DynamicImage::ImageLumaA16(_) => (gl::RG16, gl::RG, gl::UNSIGNED_SHORT, luma_alpha),
+            DynamicImage::ImageRgb16(_) => (gl::RGB16, gl::RGB, gl::UNSIGNED_SHORT, None),
+            DynamicImage::ImageRgba16(_) => (gl::RGBA16, gl::RGBA, gl::UNSIGNED_SHORT, None),
+            DynamicImage::ImageRgb32F(_) => (gl::RGB32F, gl::RGB, gl::FLOAT, None),
+            DynamicImage::ImageRgba32F(_) => (gl::RGBA32F, gl::RGBA, gl::FLOAT, None),
+            _ => return Self::prepare(DynamicImage::ImageRgba8(img.into_rgba8()), srgb),
+        };
+
+        let pixel_format = Self {
+            internal_format,
+            format,
+            pixel_type,
+            swizzle,
+        };
+        (img, pixel_format)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Texture2DBuilder {
+    wrap_s: TextureWrap,
+    wrap_t: TextureWrap,
+    min_filter: TextureFilter,
+    mag_filter: TextureFilter,
+    mipmaps: bool,
+    flip_vertically: bool,
+    srgb: bool,
+}
+
+impl Default for Texture2DBuilder {
+    fn default() -> Self {
+        Self {
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            min_filter: TextureFilter::LinearMipmapLinear,
+            mag_filter: TextureFilter::Linear,
+            mipmaps: true,
+            flip_vertically: true,
+            srgb: false,
+        }
+    }
+}
+
+impl Texture2DBuilder {
+    pub fn wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap_s = wrap;
+        self.wrap_t = wrap;
+        self
+    }
+
+    pub fn wrap_st(mut self, wrap_s: TextureWrap, wrap_t: TextureWrap) -> Self {
+        self.wrap_s = wrap_s;
+        self.wrap_t = wrap_t;
+        self
+    }
+
+    /// Mipmap filters only apply to minification, so `mag_filter` drops the mipmap part.
+    pub fn filter(mut self, min_filter: TextureFilter, mag_filter: TextureFilter) -> Self {
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter.without_mipmaps();
+        self
+    }
+
+    /// Without mipmaps only the base level is sampled, whatever the minification filter.
+    pub fn mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Image files store their top row first while OpenGL expects the bottom row first, so
+    /// images are flipped by default.
+    pub fn flip_vertically(mut self, flip_vertically: bool) -> Self {
+        self.flip_vertically = flip_vertically;
+        self
+    }
+
+    /// Stores 8-bit color images in an sRGB format so sampling returns linear values.
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    pub fn load<P>(&self, context: &GlContext, file_path: P) -> Result<Texture2D, TextureError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = file_path.as_ref();
+        let img = ImageReader::open(path)
+            .map_err(|error| TextureError::Io {
+                path: path.to_path_buf(),
+                error,
+            })?
+            .decode()
+            .map_err(|error| TextureError::Decode {
+                path: path.to_path_buf(),
+                error,
+            })?;
+
+        self.load_image(context, img)
+    }
+
+    pub fn load_image(
+        &self,
+        context: &GlContext,
+        img: DynamicImage,
+    ) -> Result<Texture2D, TextureError> {
+        let (width, height) = (img.width(), img.height());
+        if width > i32::MAX as u32 || height > i32::MAX as u32 {
+            return Err(TextureError::TooLarge { width, height });
+        }
+
+        let img = if self.flip_vertically {
+            img.flipv()
+        } else {
+            img
+        };
+        let (img, pixel_format) = PixelFormat::prepare(img, self.srgb);
+
+        // Create texture
+        let texture = Texture::new(context);
+        texture.bind(gl::TEXTURE_2D);
+
+        unsafe {
+            // Texture parameters
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                self.wrap_s.to_gl() as i32,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                self.wrap_t.to_gl() as i32,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MIN_FILTER,
+                self.min_filter.to_gl() as i32,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MAG_FILTER,
+                self.mag_filter.to_gl() as i32,
+            );
+            if let Some(swizzle) = pixel_format.swizzle {
+                gl::TexParameteriv(
+                    gl::TEXTURE_2D,
+                    gl::TEXTURE_SWIZZLE_RGBA,
+                    swizzle.map(|channel| channel as i32).as_ptr(),
+                );
+            }
+            if !self.mipmaps {
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 0);
+            }
+
+            // Set texture pixel data. Rows are tightly packed, which breaks the default 4-byte
+            // alignment for odd widths of RGB and single channel images.
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                pixel_format.internal_format as i32,
+                width as i32,
+                height as i32,
+                0,
+                pixel_format.format,
+                pixel_format.pixel_type,
+                img.as_bytes().as_ptr() as *const c_void,
+            );
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+
+            if self.mipmaps {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
+        }
+
+        Ok(Texture2D {
+            texture,
+            width,
+            height,
+        })
+    }
+}
+
+pub struct Texture2D {
+    texture: Texture,
+    width: u32,
+    height: u32,
+}
+
+impl Texture2D {
+    pub fn builder() -> Texture2DBuilder {
+        Texture2DBuilder::default()
+    }
+
+    /// Loads an image file with the default builder options.
+    pub fn from_file<P>(context: &GlContext, file_path: P) -> Result<Self, TextureError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::builder().load(context, file_path)
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.texture.id()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn bind(&self, unit: u32) {
+        unsafe { gl::ActiveTexture(gl::TEXTURE0 + unit) };
+        self.texture.bind(gl::TEXTURE_2D);
+    }
+}
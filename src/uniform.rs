@@ -0,0 +1,157 @@
+use gl::types::GLint;
+use nalgebra_glm as glm;
+
+/// A value that can be uploaded to a uniform of the currently bound program.
+pub trait Uniform {
+    /// Called by `Shader::set_uniform`, which is how uniforms are meant to be set.
+    ///
+    /// # Safety
+    ///
+    /// A `GlContext` has to be alive on this thread and `location` has to belong to the bound
+    /// program.
+    unsafe fn set(&self, location: GLint);
+}
+
+/// A value that can be uploaded as one element of a uniform array.
+pub trait UniformArrayElement: Sized {
+    /// # Safety
+    ///
+    /// Same as `Uniform::set`.
+    unsafe fn set_array(values: &[Self], location: GLint);
+}
+
+impl<T> Uniform for &T
+where
+    T: Uniform + ?Sized,
+{
+    unsafe fn set(&self, location: GLint) {
+        (**self).set(location);
+    }
+}
+
+impl<T> Uniform for [T]
+where
+    T: UniformArrayElement,
+{
+    unsafe fn set(&self, location: GLint) {
+        T::set_array(self, location);
+    }
+}
+
+impl<T, const N: usize> Uniform for [T; N]
+where
+    T: UniformArrayElement,
+{
+    unsafe fn set(&self, location: GLint) {
+        T::set_array(self, location);
+    }
+}
+
+impl<T> Uniform for Vec<T>
+where
+    T: UniformArrayElement,
+{
+    unsafe fn set(&self, location: GLint) {
+        T::set_array(self, location);
+    }
+}
+
+/// Implements `Uniform` for a single value in terms of its array upload.
+macro_rules! impl_uniform_via_array {
+    ($($ty:ty),*) => {
+        $(
+            impl Uniform for $ty {
+                unsafe fn set(&self, location: GLint) {
+                    <$ty>::set_array(std::slice::from_ref(self), location);
+                }
+            }
+        )*
+    };
+}
+
+impl_uniform_via_array!(
+    f32,
+    f64,
+    i32,
+    u32,
+    bool,
+    glm::Vec2,
+    glm::Vec3,
+    glm::Vec4,
+    glm::Mat3,
+    glm::Mat4
+);
+
+impl UniformArrayElement for f32 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform1fv(location, values.len() as i32, values.as_ptr());
+    }
+}
+
+impl UniformArrayElement for f64 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        // Unsuffixed float literals are f64, so narrowing here lets them be passed as-is
+        let values: Vec<f32> = values.iter().map(|&value| value as f32).collect();
+        f32::set_array(&values, location);
+    }
+}
+
+impl UniformArrayElement for i32 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform1iv(location, values.len() as i32, values.as_ptr());
+    }
+}
+
+impl UniformArrayElement for u32 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform1uiv(location, values.len() as i32, values.as_ptr());
+    }
+}
+
+impl UniformArrayElement for bool {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        // GLSL bools are set through the integer setters
+        let values: Vec<i32> = values.iter().map(|&value| value as i32).collect();
+        i32::set_array(&values, location);
+    }
+}
+
+impl UniformArrayElement for glm::Vec2 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform2fv(location, values.len() as i32, values.as_ptr() as *const f32);
+    }
+}
+
+impl UniformArrayElement for glm::Vec3 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform3fv(location, values.len() as i32, values.as_ptr() as *const f32);
+    }
+}
+
+impl UniformArrayElement for glm::Vec4 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::Uniform4fv(location, values.len() as i32, values.as_ptr() as *const f32);
+    }
+}
+
+impl UniformArrayElement for glm::Mat3 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::UniformMatrix3fv(
+            location,
+            values.len() as i32,
+            gl::FALSE,
+            values.as_ptr() as *const f32,
+        );
+    }
+}
+
+impl UniformArrayElement for glm::Mat4 {
+    unsafe fn set_array(values: &[Self], location: GLint) {
+        gl::UniformMatrix4fv(
+            location,
+            values.len() as i32,
+            gl::FALSE,
+            values.as_ptr() as *const f32,
+        );
+    }
+}
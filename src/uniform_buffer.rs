@@ -0,0 +1,87 @@
+use std::{ffi::c_void, marker::PhantomData};
+
+use gl::types::GLuint;
+
+use crate::{objects::Buffer, GlContext, Shader, ShaderError, Std140};
+
+/// A uniform buffer holding one `T` in std140 layout, bound to a uniform block binding point.
+/// Programs share it by binding their blocks to the same point with
+/// `Shader::bind_uniform_block`.
+pub struct UniformBuffer<T> {
+    buffer: Buffer,
+    binding: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T> UniformBuffer<T>
+where
+    T: Std140,
+{
+    /// Creates the buffer with `value` and binds it to `binding`.
+    pub fn new(context: &GlContext, binding: u32, value: &T) -> Self {
+        let bytes = value.to_std140_bytes();
+
+        let buffer = Buffer::new(context);
+        buffer.bind(gl::UNIFORM_BUFFER);
+        unsafe {
+            gl::BufferData(
+                gl::UNIFORM_BUFFER,
+                bytes.len() as isize,
+                bytes.as_ptr() as *const c_void,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+
+        let uniform_buffer = Self {
+            buffer,
+            binding,
+            _marker: PhantomData,
+        };
+        uniform_buffer.bind();
+        uniform_buffer
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.buffer.id()
+    }
+
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+
+    /// Binds the buffer to its binding point again, e.g. after another buffer took it.
+    pub fn bind(&self) {
+        unsafe { gl::BindBufferBase(gl::UNIFORM_BUFFER, self.binding, self.buffer.id()) };
+    }
+
+    /// Binds the uniform block `block_name` of `shader` to this buffer's binding point, checking
+    /// that the block fits in a `T`.
+    pub fn bind_to(&self, shader: &Shader, block_name: &str) -> Result<(), ShaderError> {
+        if let Some(block_size) = shader.uniform_block_size(block_name) {
+            if block_size > T::SIZE {
+                return Err(ShaderError::UniformBlockTooLarge {
+                    name: block_name.to_owned(),
+                    block_size,
+                    buffer_size: T::SIZE,
+                });
+            }
+        }
+        shader.bind_uniform_block(block_name, self.binding)
+    }
+
+    pub fn update(&self, value: &T) {
+        let bytes = value.to_std140_bytes();
+
+        self.buffer.bind(gl::UNIFORM_BUFFER);
+        unsafe {
+            gl::BufferSubData(
+                gl::UNIFORM_BUFFER,
+                0,
+                bytes.len() as isize,
+                bytes.as_ptr() as *const c_void,
+            );
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+}
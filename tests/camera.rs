@@ -0,0 +1,230 @@
+// Camera orientation and movement, checked on the CPU without a GL context
+
+use learn_opengl::{Camera, Plane};
+use nalgebra_glm as glm;
+
+const EPSILON: f32 = 1e-3;
+
+fn assert_vec_eq(actual: glm::Vec3, expected: glm::Vec3) {
+    assert!(
+        (actual - expected).norm() < EPSILON,
+        "{actual:?} != {expected:?}"
+    );
+}
+
+/// The eight corners of the box from `min` to `max`.
+fn corners(min: glm::Vec3, max: glm::Vec3) -> impl Iterator<Item = glm::Vec3> {
+    (0..8).map(move |i| {
+        let pick = |bit: usize, axis: usize| if i & bit == 0 { min[axis] } else { max[axis] };
+        glm::vec3(pick(1, 0), pick(2, 1), pick(4, 2))
+    })
+}
+
+fn assert_in_front(plane: &Plane, min: glm::Vec3, max: glm::Vec3) {
+    for corner in corners(min, max) {
+        assert!(
+            plane.signed_distance(&corner) >= 0.0,
+            "{corner:?} is behind {plane:?}"
+        );
+    }
+}
+
+/// Compares angles in degrees, treating full turns as equal.
+fn assert_angle_eq(actual: f32, expected: f32) {
+    let difference = (actual - expected).rem_euclid(360.0);
+    assert!(
+        difference < EPSILON || 360.0 - difference < EPSILON,
+        "{actual} != {expected}"
+    );
+}
+
+#[test]
+fn euler_angles_round_trip_through_free_fly() {
+    for yaw in [-170.0, -90.0, 0.0, 45.0, 135.0] {
+        for pitch in [-80.0, -30.0, 0.0, 60.0, 85.0] {
+            for roll in [-150.0, -20.0, 0.0, 90.0, 170.0] {
+                let camera = Camera::free_fly(glm::Vec3::zeros(), 45.0, yaw, pitch, roll);
+                let (actual_yaw, actual_pitch, actual_roll) = camera.euler_angles();
+
+                assert_angle_eq(actual_yaw, yaw);
+                assert_angle_eq(actual_pitch, pitch);
+                assert_angle_eq(actual_roll, roll);
+            }
+        }
+    }
+}
+
+#[test]
+fn free_fly_keeps_the_euler_view() {
+    for (yaw, pitch) in [(-90.0, 0.0), (30.0, 45.0), (-150.0, -70.0)] {
+        let euler = Camera::new(glm::Vec3::zeros(), 45.0, yaw, pitch);
+        let mut free_fly = Camera::new(glm::Vec3::zeros(), 45.0, yaw, pitch);
+        free_fly.set_free_fly(true);
+
+        assert!(free_fly.is_free_fly());
+        assert_vec_eq(free_fly.front(), euler.front());
+        // Without roll the camera's right stays level
+        assert!(free_fly.front().cross(&free_fly.up()).y.abs() < EPSILON);
+        let (actual_yaw, actual_pitch, actual_roll) = free_fly.euler_angles();
+        assert_angle_eq(actual_yaw, yaw);
+        assert_angle_eq(actual_pitch, pitch);
+        assert_angle_eq(actual_roll, 0.0);
+
+        // The orientation turns the default view into the camera's
+        let orientation = euler.orientation();
+        assert_vec_eq(
+            glm::quat_rotate_vec3(&orientation, &-glm::Vec3::z()),
+            euler.front(),
+        );
+    }
+}
+
+#[test]
+fn free_fly_pitches_past_straight_up() {
+    let mut camera = Camera::free_fly(glm::Vec3::zeros(), 45.0, -90.0, 0.0, 0.0);
+    for _ in 0..9 {
+        camera.look_around(0.0, 10.0);
+    }
+    assert_vec_eq(camera.front(), glm::Vec3::y());
+    assert_vec_eq(camera.up(), glm::Vec3::z());
+
+    // Turning while looking straight up still turns the view instead of locking up
+    let before = camera.front();
+    camera.look_around(30.0, 0.0);
+    assert!((before.angle(&camera.front()).to_degrees() - 30.0).abs() < EPSILON);
+    camera.look_around(-30.0, 0.0);
+
+    // Going over the top ends up upside down, looking backward
+    for _ in 0..3 {
+        camera.look_around(0.0, 10.0);
+    }
+    let angle = 120.0f32.to_radians();
+    assert_vec_eq(camera.front(), glm::vec3(0.0, angle.sin(), -angle.cos()));
+    assert_vec_eq(camera.up(), glm::vec3(0.0, angle.cos(), angle.sin()));
+}
+
+#[test]
+fn euler_pitch_stops_short_of_straight_up() {
+    let mut camera = Camera::new(glm::Vec3::zeros(), 45.0, -90.0, 0.0);
+    camera.look_around(0.0, 120.0);
+
+    let (_, pitch, _) = camera.euler_angles();
+    assert_angle_eq(pitch, 89.0);
+}
+
+#[test]
+fn move_up_follows_the_rolled_up_vector() {
+    let mut camera = Camera::free_fly(glm::Vec3::zeros(), 45.0, -90.0, 0.0, 0.0);
+    camera.roll(90.0);
+
+    // Rolling clockwise turns the camera's up to the right
+    assert_vec_eq(camera.up(), glm::Vec3::x());
+    camera.move_up(2.0);
+    assert_vec_eq(camera.position(), glm::vec3(2.0, 0.0, 0.0));
+
+    let mut camera = Camera::new(glm::Vec3::zeros(), 45.0, -90.0, 30.0);
+    camera.move_up(2.0);
+    assert_vec_eq(camera.position(), glm::vec3(0.0, 2.0, 0.0));
+}
+
+#[test]
+fn leaving_free_fly_drops_roll_and_clamps_pitch() {
+    let mut camera = Camera::free_fly(glm::Vec3::zeros(), 45.0, 30.0, 89.5, 20.0);
+    camera.set_free_fly(false);
+
+    assert!(!camera.is_free_fly());
+    let (yaw, pitch, roll) = camera.euler_angles();
+    assert_angle_eq(yaw, 30.0);
+    assert_angle_eq(pitch, 89.0);
+    assert_angle_eq(roll, 0.0);
+    assert_vec_eq(camera.up(), glm::Vec3::y());
+}
+
+#[test]
+fn orbit_around_faces_the_target_from_where_the_camera_is() {
+    let mut camera = Camera::new(glm::vec3(0.0, 0.0, 5.0), 45.0, 0.0, 0.0);
+    camera.orbit_around(glm::Vec3::zeros());
+
+    assert_eq!(camera.target(), Some(glm::Vec3::zeros()));
+    assert!((camera.distance().unwrap() - 5.0).abs() < EPSILON);
+    assert_vec_eq(camera.position(), glm::vec3(0.0, 0.0, 5.0));
+    assert_vec_eq(camera.front(), -glm::Vec3::z());
+
+    camera.stop_orbiting();
+    assert_eq!(camera.target(), None);
+    assert_vec_eq(camera.position(), glm::vec3(0.0, 0.0, 5.0));
+}
+
+#[test]
+fn orbiting_keeps_the_distance() {
+    let target = glm::vec3(1.0, 2.0, 3.0);
+    let mut camera = Camera::orbiting(target, 5.0, 45.0, -90.0, 0.0);
+
+    for (dx, dy) in [(30.0, 0.0), (45.0, 20.0), (-120.0, -60.0), (10.0, 80.0)] {
+        camera.look_around(dx, dy);
+
+        assert!(((camera.position() - target).norm() - 5.0).abs() < EPSILON);
+        assert_vec_eq(camera.position() + camera.front() * 5.0, target);
+    }
+}
+
+#[test]
+fn dolly_stops_at_the_target() {
+    let mut camera = Camera::orbiting(glm::Vec3::zeros(), 5.0, 45.0, -90.0, 0.0);
+    camera.dolly(3.0);
+    assert!((camera.distance().unwrap() - 2.0).abs() < EPSILON);
+    assert_vec_eq(camera.position(), glm::vec3(0.0, 0.0, 2.0));
+
+    camera.dolly(10.0);
+    let distance = camera.distance().unwrap();
+    assert!(distance > 0.0 && distance < 0.1);
+    // Still on the same side, facing the target
+    assert!(camera.position().z > 0.0);
+    assert_vec_eq(camera.front(), -glm::Vec3::z());
+}
+
+#[test]
+fn pan_moves_the_target() {
+    let mut camera = Camera::orbiting(glm::Vec3::zeros(), 5.0, 45.0, -90.0, 0.0);
+    camera.pan(1.0, 2.0);
+
+    assert_vec_eq(camera.target().unwrap(), glm::vec3(1.0, 2.0, 0.0));
+    assert_vec_eq(camera.position(), glm::vec3(1.0, 2.0, 5.0));
+    assert!((camera.distance().unwrap() - 5.0).abs() < EPSILON);
+    assert_vec_eq(camera.front(), -glm::Vec3::z());
+}
+
+#[test]
+fn framing_a_large_box_pushes_the_far_plane_back() {
+    // Much deeper than the default far plane at 100
+    let (min, max) = (
+        glm::vec3(-50.0, -50.0, -500.0),
+        glm::vec3(50.0, 50.0, 500.0),
+    );
+    let mut camera = Camera::new(glm::Vec3::zeros(), 45.0, -90.0, 0.0);
+    camera.frame_bounds(min, max);
+
+    assert_eq!(camera.target(), Some(glm::Vec3::zeros()));
+    let radius = (max - min).norm() * 0.5;
+    assert!(camera.far() >= camera.distance().unwrap() + radius);
+
+    let [.., near, far] = camera.frustum().planes;
+    assert_in_front(&near, min, max);
+    assert_in_front(&far, min, max);
+}
+
+#[test]
+fn framing_a_small_box_pulls_the_near_plane_in() {
+    // Close enough that the default near plane at 0.1 would cut into it
+    let (min, max) = (glm::vec3(-0.02, -0.02, -0.02), glm::vec3(0.02, 0.02, 0.02));
+    let mut camera = Camera::new(glm::Vec3::zeros(), 45.0, -90.0, 0.0);
+    camera.frame_bounds(min, max);
+
+    let radius = (max - min).norm() * 0.5;
+    assert!(camera.near() > 0.0);
+    assert!(camera.near() <= camera.distance().unwrap() - radius);
+
+    let [.., near, far] = camera.frustum().planes;
+    assert_in_front(&near, min, max);
+    assert_in_front(&far, min, max);
+}
@@ -0,0 +1,158 @@
+// Camera controller motion, driven with synthetic input instead of a window
+
+use learn_opengl::{Camera, CameraController, MovementInput};
+use nalgebra_glm as glm;
+
+const DT: f32 = 1.0 / 60.0;
+const EPSILON: f32 = 1e-4;
+
+/// A camera at the origin looking down -Z.
+fn camera() -> Camera {
+    Camera::new(glm::Vec3::zeros(), 60.0, -90.0, 0.0)
+}
+
+/// Runs `frames` updates with the same input and returns the camera.
+fn run(controller: &mut CameraController, input: MovementInput, frames: u32) -> Camera {
+    let mut camera = camera();
+    for _ in 0..frames {
+        controller.update(&mut camera, &input, DT);
+    }
+    camera
+}
+
+#[test]
+fn opposite_keys_cancel_out() {
+    let input = MovementInput {
+        forward: true,
+        backward: true,
+        ..Default::default()
+    };
+    assert_eq!(input.direction(), glm::Vec3::zeros());
+}
+
+#[test]
+fn diagonals_are_no_faster() {
+    let input = MovementInput {
+        forward: true,
+        right: true,
+        ..Default::default()
+    };
+    assert!((input.direction().norm() - 1.0).abs() < EPSILON);
+
+    let mut controller = CameraController::new().speed(2.0);
+    let camera = run(&mut controller, input, 120);
+    let position = camera.position();
+
+    // Both keys apply at once, moving forward and to the right equally
+    assert!(position.x > 0.0);
+    assert!((position.x + position.z).abs() < EPSILON);
+    assert!((controller.velocity().norm() - 2.0).abs() < EPSILON);
+}
+
+#[test]
+fn accelerates_up_to_speed() {
+    let mut controller = CameraController::new().speed(4.0).acceleration(8.0);
+    let input = MovementInput {
+        forward: true,
+        ..Default::default()
+    };
+
+    // A quarter of a second in, the camera is only halfway up to speed
+    run(&mut controller, input, 15);
+    assert!((controller.velocity().z - 2.0).abs() < EPSILON);
+
+    run(&mut controller, input, 60);
+    assert!((controller.velocity().z - 4.0).abs() < EPSILON);
+}
+
+#[test]
+fn damping_stops_the_camera_exponentially() {
+    let mut controller = CameraController::new().speed(4.0).damping(2.0);
+    let forward = MovementInput {
+        forward: true,
+        ..Default::default()
+    };
+    run(&mut controller, forward, 60);
+    assert!((controller.velocity().z - 4.0).abs() < EPSILON);
+
+    // After half a second the speed has been scaled by e^-1
+    run(&mut controller, MovementInput::default(), 30);
+    assert!((controller.velocity().z - 4.0 * (-1.0f32).exp()).abs() < 1e-3);
+
+    run(&mut controller, MovementInput::default(), 600);
+    assert_eq!(controller.velocity(), glm::Vec3::zeros());
+}
+
+#[test]
+fn sprint_and_crawl() {
+    let mut controller = CameraController::new()
+        .speed(2.0)
+        .sprint_multiplier(3.0)
+        .crawl_multiplier(0.5);
+
+    let sprint = MovementInput {
+        forward: true,
+        sprint: true,
+        ..Default::default()
+    };
+    run(&mut controller, sprint, 120);
+    assert!((controller.velocity().z - 6.0).abs() < EPSILON);
+
+    let crawl = MovementInput {
+        forward: true,
+        crawl: true,
+        ..Default::default()
+    };
+    run(&mut controller, crawl, 120);
+    assert!((controller.velocity().z - 1.0).abs() < EPSILON);
+}
+
+#[test]
+fn vertical_movement() {
+    let mut controller = CameraController::new();
+    let up = MovementInput {
+        up: true,
+        ..Default::default()
+    };
+    let camera = run(&mut controller, up, 60);
+
+    assert!(camera.position().y > 0.0);
+    assert!(camera.position().x.abs() < EPSILON);
+    assert!(camera.position().z.abs() < EPSILON);
+}
+
+#[test]
+fn first_cursor_position_does_not_turn() {
+    let mut controller = CameraController::new();
+    let mut camera = camera();
+
+    controller.cursor_moved(400.0, 300.0);
+    controller.update(&mut camera, &MovementInput::default(), DT);
+    assert_eq!(camera.euler_angles(), (-90.0, 0.0, 0.0));
+
+    // Moving the cursor up and to the right turns the camera that way
+    controller.cursor_moved(410.0, 290.0);
+    controller.update(&mut camera, &MovementInput::default(), DT);
+    let (yaw, pitch, _) = camera.euler_angles();
+    assert!((yaw + 88.0).abs() < EPSILON);
+    assert!((pitch - 2.0).abs() < EPSILON);
+}
+
+#[test]
+fn mouse_smoothing_delays_but_keeps_the_turn() {
+    let mut controller = CameraController::new()
+        .mouse_sensitivity(1.0)
+        .mouse_smoothing(0.05);
+    let mut camera = camera();
+
+    controller.look(30.0, 0.0);
+    controller.update(&mut camera, &MovementInput::default(), DT);
+    let (yaw, _, _) = camera.euler_angles();
+    assert!(yaw > -90.0 && yaw < -60.0);
+
+    for _ in 0..120 {
+        controller.update(&mut camera, &MovementInput::default(), DT);
+    }
+    let (yaw, _, _) = camera.euler_angles();
+    assert!((yaw + 60.0).abs() < 1e-2);
+}
@@ -0,0 +1,168 @@
+// Frustum plane extraction and culling, checked on the CPU without a GL context
+
+use learn_opengl::{Aabb, Camera, Frustum, Projection};
+use nalgebra_glm as glm;
+
+const EPSILON: f32 = 1e-4;
+
+/// A camera at the origin looking down -Z with a 90 degree field of view and a square
+/// viewport, so the side planes are at 45 degrees.
+fn camera() -> Camera {
+    let mut camera = Camera::new(glm::Vec3::zeros(), 90.0, -90.0, 0.0);
+    camera.set_aspect(1.0);
+    camera.set_clip_planes(1.0, 10.0);
+    camera
+}
+
+fn assert_vec_eq(actual: glm::Vec3, expected: glm::Vec3) {
+    assert!(
+        (actual - expected).norm() < EPSILON,
+        "{actual:?} != {expected:?}"
+    );
+}
+
+#[test]
+fn planes_of_a_perspective_projection() {
+    let [left, right, bottom, top, near, far] = camera().frustum().planes;
+    let side = 0.5f32.sqrt();
+
+    assert_vec_eq(left.normal, glm::vec3(side, 0.0, -side));
+    assert_vec_eq(right.normal, glm::vec3(-side, 0.0, -side));
+    assert_vec_eq(bottom.normal, glm::vec3(0.0, side, -side));
+    assert_vec_eq(top.normal, glm::vec3(0.0, -side, -side));
+    for plane in [left, right, bottom, top] {
+        assert!(plane.distance.abs() < EPSILON);
+    }
+
+    assert_vec_eq(near.normal, glm::vec3(0.0, 0.0, -1.0));
+    assert!((near.distance + 1.0).abs() < EPSILON);
+    assert_vec_eq(far.normal, glm::vec3(0.0, 0.0, 1.0));
+    assert!((far.distance - 10.0).abs() < EPSILON);
+}
+
+#[test]
+fn planes_of_an_orthographic_projection() {
+    let mut camera = camera();
+    camera.set_projection(Projection::Orthographic { height: 4.0 });
+    let frustum = camera.frustum();
+
+    for plane in &frustum.planes[..4] {
+        assert!((plane.distance - 2.0).abs() < EPSILON);
+    }
+    assert!(frustum.contains_point(&glm::vec3(1.9, -1.9, -5.0)));
+    assert!(!frustum.contains_point(&glm::vec3(2.1, 0.0, -5.0)));
+}
+
+#[test]
+fn points() {
+    let frustum = camera().frustum();
+
+    assert!(frustum.contains_point(&glm::vec3(0.0, 0.0, -5.0)));
+    assert!(frustum.contains_point(&glm::vec3(4.9, 0.0, -5.0)));
+    assert!(!frustum.contains_point(&glm::vec3(5.1, 0.0, -5.0)));
+    assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -0.5)));
+    assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -11.0)));
+    assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, 5.0)));
+}
+
+#[test]
+fn spheres() {
+    let frustum = camera().frustum();
+
+    assert!(frustum.intersects_sphere(&glm::vec3(0.0, 0.0, -5.0), 1.0));
+    // Straddling the near and far planes
+    assert!(frustum.intersects_sphere(&glm::vec3(0.0, 0.0, 0.0), 1.5));
+    assert!(frustum.intersects_sphere(&glm::vec3(0.0, 0.0, -10.5), 1.0));
+    // Entirely behind the camera, past the far plane and off to the side
+    assert!(!frustum.intersects_sphere(&glm::vec3(0.0, 0.0, 3.0), 1.0));
+    assert!(!frustum.intersects_sphere(&glm::vec3(0.0, 0.0, -12.0), 1.0));
+    assert!(!frustum.intersects_sphere(&glm::vec3(8.0, 0.0, -5.0), 1.0));
+}
+
+#[test]
+fn boxes() {
+    let frustum = camera().frustum();
+    let unit_box = |center: glm::Vec3| {
+        Aabb::new(
+            center - glm::vec3(0.5, 0.5, 0.5),
+            center + glm::vec3(0.5, 0.5, 0.5),
+        )
+    };
+
+    assert!(frustum.intersects_aabb(&unit_box(glm::vec3(0.0, 0.0, -5.0))));
+    assert!(frustum.intersects_aabb(&unit_box(glm::vec3(5.3, 0.0, -5.0))));
+    assert!(!frustum.intersects_aabb(&unit_box(glm::vec3(7.0, 0.0, -5.0))));
+    assert!(!frustum.intersects_aabb(&unit_box(glm::vec3(0.0, 0.0, 2.0))));
+    assert!(!frustum.intersects_aabb(&unit_box(glm::vec3(0.0, -8.0, -5.0))));
+
+    // A box around the whole frustum
+    let huge = Aabb::new(
+        glm::vec3(-100.0, -100.0, -100.0),
+        glm::vec3(100.0, 100.0, 100.0),
+    );
+    assert!(frustum.intersects_aabb(&huge));
+}
+
+#[test]
+fn model_space_frustum() {
+    let camera = camera();
+    let model = glm::translate(&glm::identity(), &glm::vec3(0.0, 0.0, -20.0));
+    let frustum = Frustum::from_matrix(&(camera.view_projection() * model));
+
+    // The model is moved out of view, so its origin isn't visible but a point 15 units closer
+    // to the camera is
+    assert!(!frustum.contains_point(&glm::Vec3::zeros()));
+    assert!(frustum.contains_point(&glm::vec3(0.0, 0.0, 15.0)));
+}
+
+#[test]
+fn infinite_reversed_z_has_no_far_plane() {
+    let mut camera = camera();
+    camera.set_projection(Projection::InfiniteReversedZ);
+    let frustum = camera.frustum();
+
+    assert!(frustum.contains_point(&glm::vec3(0.0, 0.0, -1.0e6)));
+    assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -0.5)));
+    assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, 5.0)));
+}
+
+#[test]
+fn infinite_reversed_z_planes_are_in_order() {
+    let mut camera = camera();
+    camera.set_projection(Projection::InfiniteReversedZ);
+    let [.., near, far] = camera.frustum().planes;
+
+    assert_vec_eq(near.normal, glm::vec3(0.0, 0.0, -1.0));
+    assert!((near.distance + 1.0).abs() < EPSILON);
+    assert_vec_eq(far.normal, glm::Vec3::zeros());
+}
+
+#[test]
+fn rotated_camera() {
+    // Looking down +X instead
+    let mut camera = Camera::new(glm::Vec3::zeros(), 90.0, 0.0, 0.0);
+    camera.set_aspect(1.0);
+    let frustum = camera.frustum();
+
+    assert!(frustum.intersects_sphere(&glm::vec3(5.0, 0.0, 0.0), 0.5));
+    assert!(!frustum.intersects_sphere(&glm::vec3(0.0, 0.0, -5.0), 0.5));
+}
+
+#[test]
+fn aabb_from_points_and_transform() {
+    let points = [
+        glm::vec3(1.0, -2.0, 0.5),
+        glm::vec3(-1.0, 3.0, 0.0),
+        glm::vec3(0.0, 0.0, -4.0),
+    ];
+    let aabb = Aabb::from_points(&points).unwrap();
+    assert_eq!(aabb.min, glm::vec3(-1.0, -2.0, -4.0));
+    assert_eq!(aabb.max, glm::vec3(1.0, 3.0, 0.5));
+    assert!(Aabb::from_points(&[]).is_none());
+
+    // A quarter turn around Y swaps the X and Z extents
+    let rotation = glm::rotate_y(&glm::identity(), std::f32::consts::FRAC_PI_2);
+    let rotated = aabb.transformed(&rotation);
+    assert_vec_eq(rotated.min, glm::vec3(-4.0, -2.0, -1.0));
+    assert_vec_eq(rotated.max, glm::vec3(0.5, 3.0, 1.0));
+}
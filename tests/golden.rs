@@ -0,0 +1,173 @@
+// Golden image tests for the section binaries
+//
+// Every binary is run headlessly through `Runner` for a fixed number of 1/60 s frames, so
+// animated scenes always end up at the same time, and the image is compared against
+// `tests/golden/<binary>.png`. The tests need an OpenGL 3.3 context, which CI machines usually
+// don't have, so they're ignored by default:
+//
+//     LIBGL_ALWAYS_SOFTWARE=1 xvfb-run cargo test --test golden -- --ignored
+//
+// `GOLDEN_TOLERANCE` is the largest per-channel difference a pixel can have and still match
+// (2 by default). `GOLDEN_BLESS=1` overwrites the references with the rendered images instead
+// of comparing. Failed comparisons write the rendered image and a diff to `target/golden`.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use image::{Rgba, RgbaImage};
+
+const WIDTH: u32 = 400;
+const HEIGHT: u32 = 300;
+const FRAMES: u32 = 30;
+const DEFAULT_TOLERANCE: u8 = 2;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+fn tolerance() -> u8 {
+    match std::env::var("GOLDEN_TOLERANCE") {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("GOLDEN_TOLERANCE should be 0-255, not `{value}`")),
+        Err(_) => DEFAULT_TOLERANCE,
+    }
+}
+
+/// Runs a section binary headlessly from the crate root, so it finds its shaders and assets.
+fn render(name: &str, exe: &str) -> RgbaImage {
+    let output_dir = manifest_dir().join("target/golden");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let output = output_dir.join(format!("{name}.actual.png"));
+    let _ = std::fs::remove_file(&output);
+
+    let result = Command::new(exe)
+        .current_dir(manifest_dir())
+        .env("LEARN_OPENGL_HEADLESS_OUTPUT", &output)
+        .env("LEARN_OPENGL_HEADLESS_FRAMES", FRAMES.to_string())
+        .env("LEARN_OPENGL_HEADLESS_SIZE", format!("{WIDTH}x{HEIGHT}"))
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run {name}: {e}"));
+    assert!(
+        result.status.success(),
+        "{name} exited with {}:\n{}",
+        result.status,
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    image::open(&output)
+        .unwrap_or_else(|e| panic!("Failed to open {}: {e}", output.display()))
+        .into_rgba8()
+}
+
+/// Marks pixels that differ by more than `tolerance` in red over a dimmed copy of `expected`,
+/// and returns the image along with how many pixels differ.
+fn diff(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> (RgbaImage, usize) {
+    let mut mismatched = 0;
+    let image = RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        let matches = a.0.iter().zip(e.0).all(|(a, e)| a.abs_diff(e) <= tolerance);
+        if matches {
+            let [r, g, b, _] = e.0;
+            Rgba([r / 4, g / 4, b / 4, 255])
+        } else {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        }
+    });
+    (image, mismatched)
+}
+
+fn check(name: &str, exe: &str) {
+    let actual = render(name, exe);
+    let reference: PathBuf = manifest_dir().join(format!("tests/golden/{name}.png"));
+
+    if env_flag("GOLDEN_BLESS") {
+        std::fs::create_dir_all(reference.parent().unwrap()).unwrap();
+        actual.save(&reference).unwrap();
+        return;
+    }
+
+    let expected = image::open(&reference)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Failed to open {} ({e}), run with GOLDEN_BLESS=1 to create it",
+                reference.display()
+            )
+        })
+        .into_rgba8();
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "{name} rendered at a different size than its reference"
+    );
+
+    let (diff, mismatched) = diff(&actual, &expected, tolerance());
+    if mismatched > 0 {
+        let diff_path = manifest_dir().join(format!("target/golden/{name}.diff.png"));
+        diff.save(&diff_path).unwrap();
+        panic!(
+            "{mismatched} pixels of {name} differ from {}, see {}",
+            reference.display(),
+            diff_path.display()
+        );
+    }
+}
+
+macro_rules! golden_tests {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[test]
+            #[ignore = "needs an OpenGL 3.3 context, run with --ignored"]
+            fn $name() {
+                check(stringify!($name), env!(concat!("CARGO_BIN_EXE_", stringify!($name))));
+            }
+        )*
+    };
+}
+
+golden_tests!(
+    section_05,
+    section_05_exercise_1,
+    section_05_exercise_2,
+    section_05_exercise_3,
+    section_06,
+    section_06_exercise_1,
+    section_06_exercise_2,
+    section_06_exercise_3,
+    section_07,
+    section_07_exercise_1,
+    section_07_exercise_2,
+    section_07_exercise_3,
+    section_07_exercise_4,
+    section_08,
+    section_08_exercise_1,
+    section_08_exercise_2,
+    section_09,
+    section_09_exercise_2,
+    section_09_exercise_3,
+    section_10,
+    section_10_exercise_1,
+    section_10_exercise_2,
+    section_12,
+    section_13,
+    section_13_exercise_1,
+    section_13_exercise_3,
+    section_14,
+    section_14_exercise_1,
+    section_14_exercise_2,
+    section_15,
+    section_15_exercise_1,
+    section_15_exercise_2,
+    section_15_exercise_3,
+    section_16,
+    section_16_exercise_1,
+    section_17,
+    section_17_exercise_1,
+);